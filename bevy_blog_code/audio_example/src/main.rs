@@ -1,6 +1,7 @@
 use bevy::{
     color::palettes::basic::*, prelude::* 
 };
+use blog_common::{action, context, ActionState, EnableInputContext, InputMapPlugin};
 
 #[derive(Component)]
 struct MyMusic;
@@ -8,6 +9,8 @@ struct MyMusic;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins(InputMapPlugin)
+        .enable_input_context(context::AUDIO)
         .add_systems(Startup, setup)
         .add_systems(Update, button_system) // button stuff
         .add_systems(Update, volume) // audio stuff
@@ -96,18 +99,16 @@ fn pause(
 }
 
 fn volume(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     music_controller: Query<&AudioSink, With<MyMusic>>,
 ) {
     if let Ok(sink) = music_controller.get_single() {
-        if keyboard_input.just_pressed(KeyCode::Equal) {
+        if actions.just_pressed(action::VOLUME_UP) {
             if sink.volume() < 4.9 {
                 sink.set_volume(sink.volume() + 0.1);
             }
-        } else if keyboard_input.just_pressed(KeyCode::Minus) {
-            if sink.volume() > 0.0 {
-                sink.set_volume(sink.volume() - 0.1);
-            }
+        } else if actions.just_pressed(action::VOLUME_DOWN) && sink.volume() > 0.0 {
+            sink.set_volume(sink.volume() - 0.1);
         }
     }
 
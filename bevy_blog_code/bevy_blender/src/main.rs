@@ -1,6 +1,5 @@
 use bevy::prelude::*;
-use bevy::window::{PrimaryWindow, Window};
-use bevy::input::mouse::MouseButton;
+use blog_common::{Grid, GridPlugin, PickEvent, PickShape, Pickable, PickingPlugin};
 
 #[derive(Component)]
 struct DonutTag;
@@ -8,8 +7,9 @@ struct DonutTag;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins((GridPlugin, PickingPlugin))
         .add_systems(Startup, setup)
-        .add_systems(Update, check_donut_click)
+        .add_systems(Update, (fit_donut_torus, check_donut_click))
         .run();
 }
 fn setup(
@@ -31,32 +31,64 @@ fn setup(
     ));
 
     commands.spawn((
-        SceneBundle {
-            scene: bevy::prelude::SceneRoot(asset_server.load("Donut.glb#Scene0")),
-            transform: Transform::from_xyz(0.0, 0.0, 0.0),
-            ..default()
-        },
-        DonutTag,
+        SceneRoot(asset_server.load("Donut.glb#Scene0")),
+        Transform::from_xyz(0.0, 0.0, 0.0),
+        DonutTag, // becomes pickable once its meshes have loaded, see fit_donut_torus
     ));
+
+    commands.spawn(Grid::default());
+}
+
+// The donut really is a torus, so it can be tested exactly. Its size comes from the box around
+// the meshes in Donut.glb: half the height is the thickness of the ring, half the width reaches to
+// the outside edge. Donut.glb is centred on its origin, like bevy's Torus.
+// This used to be `PickShape::Mesh`, which tests the real triangles and reports which one was hit.
+// donut_animation still picks the same Donut.glb that way.
+fn fit_donut_torus(
+    mut commands: Commands,
+    donuts: Query<(Entity, &GlobalTransform), (With<DonutTag>, Without<Pickable>)>,
+    children: Query<&Children>,
+    parts: Query<(&Mesh3d, &GlobalTransform)>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    'donuts: for (donut, donut_transform) in &donuts {
+        let to_donut = donut_transform.affine().inverse();
+        let mut bounds: Option<(Vec3, Vec3)> = None;
+        for (mesh, transform) in parts.iter_many(children.iter_descendants(donut)) {
+            let Some(positions) = meshes
+                .get(mesh)
+                .and_then(|mesh| mesh.attribute(Mesh::ATTRIBUTE_POSITION))
+                .and_then(|positions| positions.as_float3())
+            else {
+                continue 'donuts; // not loaded yet
+            };
+            let to_donut = to_donut * transform.affine();
+            for &position in positions {
+                let point = to_donut.transform_point3(position.into());
+                let (min, max) = bounds.get_or_insert((point, point));
+                (*min, *max) = (min.min(point), max.max(point));
+            }
+        }
+        let Some((min, max)) = bounds else { continue; }; // the scene hasn't been spawned yet
+
+        let size = max - min;
+        let minor_radius = size.y / 2.0;
+        let major_radius = (size.x + size.z) / 4.0 - minor_radius;
+        info!("Donut is a torus with radii {major_radius:.3} and {minor_radius:.3}");
+        commands.entity(donut).insert(Pickable {
+            shape: PickShape::Torus { major_radius, minor_radius },
+        });
+    }
 }
 
 fn check_donut_click(
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform)>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut picks: EventReader<PickEvent>,
+    donuts: Query<(), With<DonutTag>>,
 ) {
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        let Ok(window) = windows.get_single() else { return; };
-        let Some(cursor_pos) = window.cursor_position() else { return; };
-        let Ok((camera, camera_transform)) = cameras.get_single() else { return; };
-        let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_pos) else { return; };
-        let donut_center = Vec3::ZERO; // assuming donut at origin
-        let radius = 1.0; // adjust as needed
-        let origin_to_center = donut_center - ray.origin;
-        let tca = origin_to_center.dot(ray.direction.as_vec3());
-        let d2 = origin_to_center.length_squared() - tca * tca;
-        if d2 <= radius * radius {
-            println!("Donut clicked!");
+    for pick in picks.read() {
+        if donuts.contains(pick.entity) {
+            let point = pick.hit_point;
+            info!("Donut clicked at ({:.2}, {:.2}, {:.2})", point.x, point.y, point.z);
         }
     }
 }
\ No newline at end of file
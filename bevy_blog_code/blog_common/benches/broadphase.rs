@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use blog_common::{spatial_hash::SpatialHash, Random};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// About twice the size of the obstacles, like the cells the collisions example uses
+const CELL_SIZE: f32 = 50.0;
+
+// Boxes 10 to 40 wide, spread out so that there's always about the same number of them in each spot
+fn scatter(count: usize) -> Vec<(Entity, Rect)> {
+    let half_size = (count as f32).sqrt() * 50.0;
+    let mut random = Random(12345);
+    (0..count)
+        .map(|i| {
+            let center = random.point(half_size);
+            let size = random.range(10.0, 40.0);
+            (Entity::from_raw(i as u32), Rect::from_center_size(center, Vec2::splat(size)))
+        })
+        .collect()
+}
+
+fn build_hash(boxes: &[(Entity, Rect)]) -> SpatialHash {
+    let mut hash = SpatialHash::new(CELL_SIZE);
+    for &(entity, bounds) in boxes {
+        hash.insert(entity, bounds);
+    }
+    hash
+}
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.min.cmple(b.max).all() && b.min.cmple(a.max).all()
+}
+
+fn brute_force_query(boxes: &[(Entity, Rect)], region: Rect) -> Vec<Entity> {
+    boxes.iter().filter(|(_, bounds)| overlaps(*bounds, region)).map(|(entity, _)| *entity).collect()
+}
+
+fn brute_force_pairs(boxes: &[(Entity, Rect)]) -> usize {
+    let mut pairs = 0;
+    for (i, (_, a)) in boxes.iter().enumerate() {
+        for (_, b) in &boxes[i + 1..] {
+            if overlaps(*a, *b) {
+                pairs += 1;
+            }
+        }
+    }
+    pairs
+}
+
+fn spatial_hash_pairs(boxes: &[(Entity, Rect)], hash: &SpatialHash) -> usize {
+    let mut pairs = 0;
+    for &(a, bounds) in boxes {
+        pairs += hash.query(bounds).into_iter().filter(|b| a < *b).count();
+    }
+    pairs
+}
+
+// What a moving player does every frame: look around the spot it's moving to
+fn query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("query");
+    for count in [100, 1_000, 10_000] {
+        let boxes = scatter(count);
+        let hash = build_hash(&boxes);
+        let half_size = (count as f32).sqrt() * 50.0;
+        let mut random = Random(999);
+        let regions: Vec<Rect> =
+            (0..100).map(|_| Rect::from_center_size(random.point(half_size), Vec2::splat(60.0))).collect();
+
+        for region in &regions {
+            let mut expected = brute_force_query(&boxes, *region);
+            let mut found = hash.query(*region);
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected, "the spatial hash and brute force disagree");
+        }
+
+        group.bench_with_input(BenchmarkId::new("brute_force", count), &regions, |b, regions| {
+            b.iter(|| regions.iter().map(|region| brute_force_query(&boxes, *region).len()).sum::<usize>())
+        });
+        group.bench_with_input(BenchmarkId::new("spatial_hash", count), &regions, |b, regions| {
+            b.iter(|| regions.iter().map(|region| hash.query(*region).len()).sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+// Every overlapping pair, like `CollisionPlugin` needs when everything moves at once
+fn all_pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("all_pairs");
+    group.sample_size(10);
+    for count in [1_000, 10_000] {
+        let boxes = scatter(count);
+        let hash = build_hash(&boxes);
+        assert_eq!(spatial_hash_pairs(&boxes, &hash), brute_force_pairs(&boxes));
+
+        group.bench_with_input(BenchmarkId::new("brute_force", count), &boxes, |b, boxes| {
+            b.iter(|| brute_force_pairs(black_box(boxes)))
+        });
+        group.bench_with_input(BenchmarkId::new("spatial_hash", count), &boxes, |b, boxes| {
+            b.iter(|| spatial_hash_pairs(black_box(boxes), &hash))
+        });
+    }
+    group.finish();
+}
+
+// Moving everything a little, updating the hash in place against building it again from nothing
+fn update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+    for count in [1_000, 10_000] {
+        let boxes = scatter(count);
+        let mut hash = build_hash(&boxes);
+        let mut frame = 0.0f32;
+
+        group.bench_function(BenchmarkId::new("incremental", count), |b| {
+            b.iter(|| {
+                frame += 1.0;
+                let offset = Vec2::new(frame.sin(), frame.cos()) * 5.0;
+                for &(entity, bounds) in &boxes {
+                    hash.insert(entity, Rect::from_center_size(bounds.center() + offset, bounds.size()));
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("rebuild", count), |b| {
+            b.iter(|| build_hash(black_box(&boxes)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, query, all_pairs, update);
+criterion_main!(benches);
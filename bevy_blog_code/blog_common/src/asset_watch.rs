@@ -0,0 +1,85 @@
+use std::{marker::PhantomData, time::SystemTime};
+
+use bevy::{
+    asset::{io::file::FileAssetReader, AssetLoadFailedEvent, AssetPath},
+    prelude::*,
+    utils::HashMap,
+};
+
+// Reloads assets of type `A` when their files in the `assets` folder change, so levels and maps
+// can be edited while an example runs. Files that didn't load are watched too, so fixing them loads them.
+//
+// Bevy can watch asset files by itself with its `file_watcher` feature, but that brings in more crates.
+// Instead this looks at when each file last changed every time the `AssetWatch` timer goes off,
+// so the file system isn't asked every frame.
+pub struct AssetWatchPlugin<A>(PhantomData<A>);
+
+impl<A> Default for AssetWatchPlugin<A> {
+    fn default() -> Self {
+        AssetWatchPlugin(PhantomData)
+    }
+}
+
+impl<A: Asset> Plugin for AssetWatchPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetWatch<A>>().add_systems(Update, watch_asset_files::<A>);
+    }
+}
+
+// When each file was last changed, as far as `watch_asset_files` knows, and when to look again
+#[derive(Resource)]
+pub struct AssetWatch<A> {
+    last_changed: HashMap<AssetPath<'static>, Option<SystemTime>>,
+    timer: Timer,
+    asset: PhantomData<A>,
+}
+
+impl<A> Default for AssetWatch<A> {
+    fn default() -> Self {
+        // twice a second is plenty for files a person is editing
+        AssetWatch { last_changed: default(), timer: Timer::from_seconds(0.5, TimerMode::Repeating), asset: PhantomData }
+    }
+}
+
+impl<A> AssetWatch<A> {
+    // Takes the file as it is now as already loaded, so writing out what's on screen doesn't reload it
+    pub fn saved<'a>(&mut self, path: impl Into<AssetPath<'a>>) {
+        let path = path.into().into_owned();
+        let changed = last_changed(&path);
+        self.last_changed.insert(path, changed);
+    }
+}
+
+fn last_changed(path: &AssetPath) -> Option<SystemTime> {
+    let file = FileAssetReader::get_base_path().join("assets").join(path.path());
+    std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn watch_asset_files<A: Asset>(
+    time: Res<Time>,
+    mut watch: ResMut<AssetWatch<A>>,
+    mut failures: EventReader<AssetLoadFailedEvent<A>>,
+    assets: Res<Assets<A>>,
+    asset_server: Res<AssetServer>,
+) {
+    for failure in failures.read() {
+        watch.last_changed.entry(failure.path.clone()).or_default();
+    }
+    if !watch.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for id in assets.ids() {
+        if let Some(path) = asset_server.get_path(id) {
+            watch.last_changed.entry(path.into_owned()).or_default();
+        }
+    }
+
+    for (path, last) in &mut watch.last_changed {
+        let Some(changed) = last_changed(path) else { continue; };
+        if last.is_some_and(|last| last != changed) {
+            info!("{path} changed, reloading");
+            asset_server.reload(path.clone());
+        }
+        *last = Some(changed);
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, f32::consts::PI};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fly_camera::{fly_camera, FlyCamera},
+    input::{action, context, ActionState, EnableInputContext},
+};
+
+// Saves the fly camera's position to numbered slots and flies back to them later.
+// Hold Ctrl and press 1-9 to save, press 1-9 alone to go back.
+// The slots are written to `assets/camera_bookmarks.ron`, so every example keeps its own.
+pub struct CameraBookmarksPlugin;
+
+impl Plugin for CameraBookmarksPlugin {
+    fn build(&self, app: &mut App) {
+        app.enable_input_context(context::BOOKMARKS)
+            .insert_resource(CameraBookmarks::load_or_default())
+            .add_systems(Update, (save_or_recall_bookmark, move_to_bookmark.after(fly_camera)));
+    }
+}
+
+// Where the camera is and which way it looks
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub translation: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+#[derive(Resource, Debug, Serialize, Deserialize)]
+pub struct CameraBookmarks {
+    pub slots: BTreeMap<u8, CameraPose>,
+    #[serde(default = "default_transition_secs")]
+    pub transition_secs: f32, // how long flying to a bookmark takes
+}
+
+fn default_transition_secs() -> f32 {
+    1.0
+}
+
+impl Default for CameraBookmarks {
+    fn default() -> Self {
+        CameraBookmarks {
+            slots: BTreeMap::new(),
+            transition_secs: default_transition_secs(),
+        }
+    }
+}
+
+impl CameraBookmarks {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn path() -> std::path::PathBuf {
+        bevy::asset::io::file::FileAssetReader::get_base_path().join("assets/camera_bookmarks.ron")
+    }
+
+    pub fn load_or_default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = Self::path();
+            if let Ok(text) = std::fs::read_to_string(&path) {
+                match ron::from_str(&text) {
+                    Ok(bookmarks) => return bookmarks,
+                    Err(err) => error!("Could not read {}: {err}, starting with no bookmarks", path.display()),
+                }
+            }
+        }
+        CameraBookmarks::default()
+    }
+
+    pub fn save(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = Self::path();
+            let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                .map_err(|err| err.to_string())
+                .and_then(|text| {
+                    if let Some(folder) = path.parent() {
+                        std::fs::create_dir_all(folder).map_err(|err| err.to_string())?;
+                    }
+                    std::fs::write(&path, text).map_err(|err| err.to_string())
+                });
+            if let Err(err) = result {
+                error!("Could not save camera bookmarks to {}: {err}", path.display());
+            }
+        }
+    }
+}
+
+// A camera on its way to a bookmark
+#[derive(Component)]
+pub struct CameraTransition {
+    from: CameraPose,
+    to: CameraPose,
+    timer: f32,
+}
+
+fn save_or_recall_bookmark(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    cameras: Query<(Entity, &Transform, &FlyCamera)>,
+) {
+    let Some(slot) = (1..=9).find(|&slot| actions.just_pressed(action::BOOKMARKS[slot as usize - 1])) else {
+        return;
+    };
+
+    for (entity, transform, camera) in &cameras {
+        let pose = CameraPose {
+            translation: transform.translation,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+        };
+
+        if actions.pressed(action::BOOKMARK_SAVE) {
+            bookmarks.slots.insert(slot, pose);
+            bookmarks.save();
+            info!("Saved camera bookmark {slot}");
+        } else if let Some(&to) = bookmarks.slots.get(&slot) {
+            commands.entity(entity).insert(CameraTransition {
+                from: pose,
+                to,
+                timer: 0.0,
+            });
+        }
+    }
+}
+
+// Runs after `fly_camera` so the transition wins over the keyboard until it's done
+fn move_to_bookmark(
+    mut commands: Commands,
+    time: Res<Time>,
+    bookmarks: Res<CameraBookmarks>,
+    mut cameras: Query<(Entity, &mut Transform, &mut FlyCamera, &mut CameraTransition)>,
+) {
+    for (entity, mut transform, mut camera, mut transition) in &mut cameras {
+        transition.timer += time.delta_secs();
+        let t = (transition.timer / bookmarks.transition_secs.max(f32::EPSILON)).min(1.0);
+        let eased = t * t * (3.0 - 2.0 * t); // smoothstep: slow start, slow finish
+
+        // turn the short way round, so going from 350° to 10° is a 20° turn and not 340°
+        let yaw_change = (transition.to.yaw - transition.from.yaw + PI).rem_euclid(2.0 * PI) - PI;
+
+        transform.translation = transition.from.translation.lerp(transition.to.translation, eased);
+        camera.yaw = transition.from.yaw + yaw_change * eased;
+        camera.pitch = transition.from.pitch + (transition.to.pitch - transition.from.pitch) * eased;
+        transform.rotation =
+            Quat::from_axis_angle(Vec3::Y, camera.yaw) * Quat::from_axis_angle(Vec3::X, camera.pitch);
+
+        if t >= 1.0 {
+            commands.entity(entity).remove::<CameraTransition>();
+        }
+    }
+}
@@ -0,0 +1,534 @@
+use bevy::{
+    prelude::*,
+    transform::TransformSystem,
+    utils::{HashMap, HashSet},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::spatial_hash::SpatialHash;
+
+// 2D collision shapes and the test for whether two of them overlap.
+// Every shape is a "core" (a point, a line segment or a convex polygon) with a rounded edge around it:
+// a circle is a point with a radius, a capsule is a segment with a radius, and boxes and polygons have none.
+// That way one test works for every pair of shapes instead of writing each pair by hand.
+
+// Checks entities with a `Collider` against their neighbours after they've moved each frame, and sends
+// `CollisionStarted` when two begin to overlap and `CollisionEnded` when they come apart.
+// Movement code that stops things before they overlap can `report` what blocked them, so those count too.
+// The neighbours come from a `SpatialHash` resource, which movement code can use to find nearby obstacles.
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CollisionStarted>()
+            .add_event::<CollisionEnded>()
+            .init_resource::<Collisions>()
+            .init_resource::<SpatialHash>()
+            .add_systems(
+                PostUpdate,
+                (update_spatial_hash, update_collisions).chain().after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+// A shape in its entity's own space, centred on the origin. In a RON file it reads like `Circle(radius: 50.0)`.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Collider {
+    Circle { radius: f32 },
+    Aabb { half_extents: Vec2 }, // a box that stays lined up with the axes, the rotation is ignored
+    Obb { half_extents: Vec2 },  // a box that turns with the entity
+    Capsule { half_length: f32, radius: f32 }, // a rounded stick along Y, like bevy's Capsule2d
+    Polygon { points: Vec<Vec2> }, // must be convex, the points can go either way around
+}
+
+// How two shapes overlap
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    pub normal: Vec2, // points from the first shape towards the second
+    pub depth: f32,   // move the first shape back along the normal this far to separate them
+    pub point: Vec2,  // where they touch, in world space, halfway through the overlap
+}
+
+impl Contact {
+    // The same contact seen from the second shape
+    pub fn flipped(self) -> Contact {
+        Contact { normal: -self.normal, ..self }
+    }
+}
+
+// The contact normal points from `a` to `b`
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionStarted {
+    pub a: Entity,
+    pub b: Entity,
+    pub contact: Contact,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionEnded {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+// The pairs of entities that are touching. Each pair is only in here once, whichever way round.
+#[derive(Resource, Default, Debug)]
+pub struct Collisions {
+    touching: HashMap<(Entity, Entity), Contact>, // as of the end of the last frame
+    reported: HashMap<(Entity, Entity), Contact>, // found so far this frame
+}
+
+impl Collisions {
+    // Counts `a` and `b` as touching this frame, for movement that stops before they overlap
+    pub fn report(&mut self, a: Entity, b: Entity, contact: Contact) {
+        let (pair, contact) = if a < b { ((a, b), contact) } else { ((b, a), contact.flipped()) };
+        self.reported.insert(pair, contact);
+    }
+
+    // How `a` touches `b`, with the normal pointing from `a` to `b`
+    pub fn contact(&self, a: Entity, b: Entity) -> Option<Contact> {
+        if a < b {
+            self.touching.get(&(a, b)).copied()
+        } else {
+            self.touching.get(&(b, a)).map(|contact| contact.flipped())
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity, Contact)> + '_ {
+        self.touching.iter().map(|(&(a, b), &contact)| (a, b, contact))
+    }
+}
+
+// The entity's position and turn on the XY plane. Scale is ignored, size the `Collider` instead.
+pub fn isometry_2d(transform: &GlobalTransform) -> Isometry2d {
+    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+    let (angle, _, _) = rotation.to_euler(EulerRot::ZYX);
+    Isometry2d::new(translation.truncate(), Rot2::radians(angle))
+}
+
+// Only colliders that moved, turned or changed shape need a new spot in the hash
+fn update_spatial_hash(
+    changed: Query<(Entity, &GlobalTransform, &Collider), Or<(Changed<GlobalTransform>, Changed<Collider>)>>,
+    mut removed: RemovedComponents<Collider>,
+    mut hash: ResMut<SpatialHash>,
+) {
+    for entity in removed.read() {
+        hash.remove(entity);
+    }
+    for (entity, transform, collider) in &changed {
+        hash.insert(entity, collider.bounds(isometry_2d(transform)));
+    }
+}
+
+fn update_collisions(
+    colliders: Query<(&GlobalTransform, &Collider)>,
+    moved: Query<Entity, (With<Collider>, Or<(Changed<GlobalTransform>, Changed<Collider>)>)>,
+    hash: Res<SpatialHash>,
+    mut collisions: ResMut<Collisions>,
+    mut started: EventWriter<CollisionStarted>,
+    mut ended: EventWriter<CollisionEnded>,
+) {
+    let moved: HashSet<Entity> = moved.iter().collect();
+    let collisions = &mut *collisions;
+
+    // two things that both stood still are touching the same way as last frame
+    for (&(a, b), &contact) in &collisions.touching {
+        let still = !moved.contains(&a) && !moved.contains(&b);
+        if still && colliders.contains(a) && colliders.contains(b) {
+            collisions.reported.entry((a, b)).or_insert(contact);
+        }
+    }
+
+    // everything that moved is checked against whatever is near it
+    for &a in &moved {
+        let Ok((a_transform, a_collider)) = colliders.get(a) else { continue; };
+        let a_at = isometry_2d(a_transform);
+        for b in hash.query(a_collider.bounds(a_at)) {
+            if b == a || (moved.contains(&b) && b < a) {
+                continue; // when both moved, the pair is only checked from one side
+            }
+            let Ok((b_transform, b_collider)) = colliders.get(b) else { continue; };
+            if let Some(contact) = collide(a_collider, a_at, b_collider, isometry_2d(b_transform)) {
+                collisions.report(a, b, contact);
+            }
+        }
+    }
+
+    for (&(a, b), &contact) in &collisions.reported {
+        if !collisions.touching.contains_key(&(a, b)) {
+            started.send(CollisionStarted { a, b, contact });
+        }
+    }
+    for &(a, b) in collisions.touching.keys() {
+        if !collisions.reported.contains_key(&(a, b)) {
+            ended.send(CollisionEnded { a, b });
+        }
+    }
+    collisions.touching = std::mem::take(&mut collisions.reported);
+}
+
+// Where the shapes overlap, or None if they don't. Shapes that only just touch don't count.
+pub fn collide(a: &Collider, a_at: Isometry2d, b: &Collider, b_at: Isometry2d) -> Option<Contact> {
+    let (a_core, a_radius) = a.core(a_at);
+    let (b_core, b_radius) = b.core(b_at);
+    let radius = a_radius + b_radius;
+
+    let (normal, depth) = match overlapping_cores(&a_core, &b_core) {
+        // the cores themselves overlap, push apart along the axis where that's shortest
+        Some((normal, overlap)) => (normal, overlap + radius),
+        // the cores are apart, only the rounded edges can touch
+        None => {
+            let (a_closest, b_closest) = closest_points(&a_core, &b_core);
+            let distance = a_closest.distance(b_closest);
+            if distance >= radius {
+                return None;
+            }
+            // two circles exactly on top of each other have no direction between them, any will do
+            let normal = (b_closest - a_closest).try_normalize().unwrap_or(Vec2::Y);
+            (normal, radius - distance)
+        }
+    };
+    if depth <= 0.0 {
+        return None;
+    }
+
+    // The point is in the middle of the overlap, between the deepest parts of both shapes.
+    // Across the normal it's in the middle of where the touching faces line up, for boxes resting on each other.
+    let across = normal.perp();
+    let (a_reach, a_face) = support(&a_core, normal, across);
+    let (b_reach, b_face) = support(&b_core, -normal, across);
+    let a_surface = a_reach + a_radius;
+    let b_surface = -b_reach - b_radius;
+    let along = (a_surface + b_surface) / 2.0;
+    let point = normal * along + across * ((a_face.0.max(b_face.0) + a_face.1.min(b_face.1)) / 2.0);
+
+    Some(Contact { normal, depth, point })
+}
+
+// Where `move_and_slide` left the shape, and what it bumped into on the way.
+// The obstacles are named by whatever key they were passed in with, usually their entity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slide<K = Entity> {
+    pub position: Vec2,
+    pub hits: Vec<(K, Contact)>,
+}
+
+// How many times the leftover motion gets another go after hitting something.
+// Two is enough for a corner between two walls, the rest are for awkward spots between round obstacles.
+const SLIDE_ITERATIONS: usize = 4;
+// How many halvings it takes to find where a shape that isn't a circle first touches an obstacle
+const SLIDE_SEARCH_STEPS: usize = 12;
+// How far a slide stops short of what it hits, as a fraction of the mover's size, so it doesn't start
+// the next move already touching
+const SLIDE_SKIN: f32 = 1e-3;
+
+// Moves the shape by `motion`, and when something is in the way it stops just short of it and
+// slides the rest of the way along its surface instead of throwing the whole move away.
+// It starts by pushing the shape out of anything it's already overlapping, for example an obstacle
+// that moved into it, so it can't get stuck inside.
+// Circles are swept along the whole move, so they can't skip past thin obstacles however fast they go.
+// Other shapes are only checked where the move ends, so keep their moves shorter than the obstacles.
+// Each obstacle comes with a key, like its entity or a tile position, to say which one was hit.
+pub fn move_and_slide<K: Copy>(
+    collider: &Collider,
+    at: Isometry2d,
+    motion: Vec2,
+    obstacles: &[(K, &Collider, Isometry2d)],
+) -> Slide<K> {
+    let deepest_at = |position: Vec2| {
+        let at = Isometry2d::new(position, at.rotation);
+        obstacles
+            .iter()
+            .filter_map(|&(key, obstacle, obstacle_at)| {
+                collide(collider, at, obstacle, obstacle_at).map(|contact| (key, contact))
+            })
+            .max_by(|a, b| a.1.depth.total_cmp(&b.1.depth))
+    };
+    let mut slide = Slide { position: at.translation, hits: Vec::new() };
+
+    // back out of overlaps, the deepest first since pushing out of that one can fix the others
+    for _ in 0..SLIDE_ITERATIONS {
+        let Some((key, contact)) = deepest_at(slide.position) else { break; };
+        slide.position -= contact.normal * contact.depth;
+        slide.hits.push((key, contact));
+    }
+
+    let mut remaining = motion;
+    for _ in 0..SLIDE_ITERATIONS {
+        if remaining == Vec2::ZERO {
+            break;
+        }
+        // how far along the move it gets before touching something, from 0.0 to 1.0
+        let hit = match collider {
+            Collider::Circle { radius } => obstacles
+                .iter()
+                .filter_map(|&(key, obstacle, obstacle_at)| {
+                    let (time, contact) = time_of_impact(*radius, slide.position, remaining, obstacle, obstacle_at)?;
+                    let skin = SLIDE_SKIN * radius / remaining.length();
+                    Some(((time - skin).max(0.0), key, contact))
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0)),
+            _ => first_touch(|time| deepest_at(slide.position + remaining * time)),
+        };
+        let Some((free, key, contact)) = hit else {
+            slide.position += remaining;
+            break;
+        };
+        slide.hits.push((key, contact));
+        slide.position += remaining * free;
+
+        // keep the part of what's left that runs along the surface, drop the part going into it
+        let left = remaining * (1.0 - free);
+        remaining = left - contact.normal * left.dot(contact.normal).max(0.0);
+    }
+    slide
+}
+
+// For shapes that can't be swept: if the end of the move is blocked, halve the way back to find
+// about where it first touches. Returns how far it gets while still clear, and what it touches.
+fn first_touch<K>(deepest_at: impl Fn(f32) -> Option<(K, Contact)>) -> Option<(f32, K, Contact)> {
+    deepest_at(1.0)?;
+    // `free` is always clear and `blocked` never is
+    let (mut free, mut blocked) = (0.0, 1.0);
+    for _ in 0..SLIDE_SEARCH_STEPS {
+        let middle = (free + blocked) / 2.0;
+        if deepest_at(middle).is_some() {
+            blocked = middle;
+        } else {
+            free = middle;
+        }
+    }
+    let (key, contact) = deepest_at(blocked)?;
+    Some((free, key, contact))
+}
+
+// When a circle moving from `start` by `motion` first touches the obstacle, as a fraction of the move
+// from 0.0 to 1.0, and the contact there. The contact has no depth, the shapes only just touch.
+// If they already overlap at the start that's a hit at 0.0 with the overlap's contact.
+// Works like a ray cast: the circle shrinks to a point, and the obstacle grows by the circle's radius
+// into its outline pushed out by that much, with rounded corners.
+pub fn time_of_impact(
+    radius: f32,
+    start: Vec2,
+    motion: Vec2,
+    obstacle: &Collider,
+    obstacle_at: Isometry2d,
+) -> Option<(f32, Contact)> {
+    let circle = Collider::Circle { radius };
+    if let Some(contact) = collide(&circle, Isometry2d::from_translation(start), obstacle, obstacle_at) {
+        return Some((0.0, contact));
+    }
+    if motion == Vec2::ZERO {
+        return None;
+    }
+    let (core, obstacle_radius) = obstacle.core(obstacle_at);
+    let grown = radius + obstacle_radius;
+
+    // the earliest time and the obstacle's outward normal where the point crosses into the grown outline
+    let mut first: Option<(f32, Vec2)> = None;
+    let mut consider = |time: f32, outward: Vec2| {
+        if (0.0..=1.0).contains(&time) && first.is_none_or(|(earliest, _)| time < earliest) {
+            first = Some((time, outward));
+        }
+    };
+
+    // the flat sides, each pushed out along its normal
+    let middle = core.iter().sum::<Vec2>() / core.len() as f32;
+    for (a, b) in edges(&core) {
+        let Some(along) = (b - a).try_normalize() else { continue; };
+        // a segment has two sides, a polygon's edges only face away from its middle
+        let normal = along.perp();
+        let sides = match core.len() {
+            2 => vec![normal, -normal],
+            _ if normal.dot(a - middle) < 0.0 => vec![-normal],
+            _ => vec![normal],
+        };
+        for outward in sides {
+            let closing = motion.dot(outward);
+            if closing >= 0.0 {
+                continue; // moving away from this side, or alongside it
+            }
+            let time = (grown - (start - a).dot(outward)) / closing;
+            let along_edge = (start + motion * time - a).dot(along);
+            if (0.0..=(b - a).length()).contains(&along_edge) {
+                consider(time, outward);
+            }
+        }
+    }
+    // the rounded corners, each a circle the size of the grown radius
+    for &corner in &core {
+        if let Some(time) = ray_circle(start - corner, motion, grown) {
+            consider(time, (start + motion * time - corner) / grown);
+        }
+    }
+
+    let (time, outward) = first?;
+    let normal = -outward;
+    let point = start + motion * time + normal * radius;
+    Some((time, Contact { normal, depth: 0.0, point }))
+}
+
+// When a point starting at `offset` from a circle's middle first enters the circle, as a fraction of `motion`
+fn ray_circle(offset: Vec2, motion: Vec2, radius: f32) -> Option<f32> {
+    let a = motion.length_squared();
+    let b = 2.0 * offset.dot(motion);
+    if b >= 0.0 {
+        return None; // moving away from the middle or around it, like sliding past a corner it's touching
+    }
+    let c = offset.length_squared() - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    Some((-b - discriminant.sqrt()) / (2.0 * a))
+}
+
+impl Collider {
+    // An outline of the shape, for examples and for seeing what the collisions are doing
+    pub fn draw(&self, gizmos: &mut Gizmos, at: Isometry2d, color: impl Into<Color>) {
+        let color = color.into();
+        match self {
+            Collider::Circle { radius } => {
+                gizmos.circle_2d(at, *radius, color);
+            }
+            Collider::Aabb { half_extents } => {
+                gizmos.rect_2d(at.translation, *half_extents * 2.0, color);
+            }
+            Collider::Obb { half_extents } => {
+                gizmos.rect_2d(at, *half_extents * 2.0, color);
+            }
+            Collider::Capsule { half_length, radius } => {
+                gizmos.primitive_2d(&Capsule2d::new(*radius, half_length * 2.0), at, color);
+            }
+            Collider::Polygon { points } => {
+                let corners = points.iter().chain(points.first()).map(|point| at * *point);
+                gizmos.linestrip_2d(corners, color);
+            }
+        }
+    }
+
+    // The smallest rectangle around the shape, lined up with the axes
+    pub fn bounds(&self, at: Isometry2d) -> Rect {
+        let (core, radius) = self.core(at);
+        let start = Rect::from_center_size(core[0], Vec2::ZERO);
+        core.iter().fold(start, |rect, corner| rect.union_point(*corner)).inflate(radius)
+    }
+
+    // The corners of the shape's core in world space, and the radius rounding it off
+    fn core(&self, at: Isometry2d) -> (Vec<Vec2>, f32) {
+        match self {
+            Collider::Circle { radius } => (vec![at.translation], *radius),
+            Collider::Aabb { half_extents } => {
+                (box_corners(*half_extents, Isometry2d::from_translation(at.translation)), 0.0)
+            }
+            Collider::Obb { half_extents } => (box_corners(*half_extents, at), 0.0),
+            Collider::Capsule { half_length, radius } => {
+                let ends = vec![at * Vec2::new(0.0, -half_length), at * Vec2::new(0.0, *half_length)];
+                (ends, *radius)
+            }
+            Collider::Polygon { points } => (points.iter().map(|point| at * *point).collect(), 0.0),
+        }
+    }
+}
+
+fn box_corners(half_extents: Vec2, at: Isometry2d) -> Vec<Vec2> {
+    let Vec2 { x, y } = half_extents;
+    [Vec2::new(-x, -y), Vec2::new(x, -y), Vec2::new(x, y), Vec2::new(-x, y)]
+        .into_iter()
+        .map(|corner| at * corner)
+        .collect()
+}
+
+// The edges of a core. A point is an edge with no length, and a segment is one edge, not two.
+fn edges(core: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    let count = match core.len() {
+        1 | 2 => 1,
+        n => n,
+    };
+    (0..count).map(move |i| (core[i], core[(i + 1) % core.len()]))
+}
+
+// Directions that could separate the cores: every edge's normal, and for a segment its direction too,
+// because two segments on the same line can only be told apart along it
+fn axes(core: &[Vec2]) -> Vec<Vec2> {
+    let mut axes: Vec<Vec2> = edges(core).filter_map(|(start, end)| (end - start).perp().try_normalize()).collect();
+    if core.len() == 2 {
+        axes.extend((core[1] - core[0]).try_normalize());
+    }
+    axes
+}
+
+fn project(core: &[Vec2], axis: Vec2) -> (f32, f32) {
+    core.iter()
+        .map(|corner| corner.dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), along| (min.min(along), max.max(along)))
+}
+
+// Separating axis test. If no axis separates them the cores overlap, and the axis where they overlap
+// least is the quickest way out. Returns that axis pointing from A to B, and the overlap along it.
+fn overlapping_cores(a: &[Vec2], b: &[Vec2]) -> Option<(Vec2, f32)> {
+    let mut best: Option<(Vec2, f32)> = None;
+    for axis in axes(a).into_iter().chain(axes(b)) {
+        let (a_min, a_max) = project(a, axis);
+        let (b_min, b_max) = project(b, axis);
+        let (overlap, normal) = if a_max - b_min < b_max - a_min {
+            (a_max - b_min, axis) // B sits further along the axis
+        } else {
+            (b_max - a_min, -axis)
+        };
+        if overlap < 0.0 {
+            return None;
+        }
+        if best.is_none_or(|(_, least)| overlap < least) {
+            best = Some((normal, overlap));
+        }
+    }
+    best
+}
+
+fn closest_point_on_segment(point: Vec2, start: Vec2, end: Vec2) -> Vec2 {
+    let edge = end - start;
+    let length_squared = edge.length_squared();
+    if length_squared == 0.0 {
+        return start;
+    }
+    start + edge * ((point - start).dot(edge) / length_squared).clamp(0.0, 1.0)
+}
+
+// The closest pair of points on two cores that don't overlap. One of them is always a corner.
+fn closest_points(a: &[Vec2], b: &[Vec2]) -> (Vec2, Vec2) {
+    let mut best = (a[0], b[0]);
+    let mut best_distance = f32::INFINITY;
+    let mut consider = |a_point: Vec2, b_point: Vec2| {
+        let distance = a_point.distance_squared(b_point);
+        if distance < best_distance {
+            best_distance = distance;
+            best = (a_point, b_point);
+        }
+    };
+    for &corner in a {
+        for (start, end) in edges(b) {
+            consider(corner, closest_point_on_segment(corner, start, end));
+        }
+    }
+    for &corner in b {
+        for (start, end) in edges(a) {
+            consider(closest_point_on_segment(corner, start, end), corner);
+        }
+    }
+    best
+}
+
+// How far the core reaches along a direction, and the span along `across` of the corners
+// that reach that far. That's one corner, or two when an edge faces the direction.
+fn support(core: &[Vec2], direction: Vec2, across: Vec2) -> (f32, (f32, f32)) {
+    let reach = core.iter().map(|corner| corner.dot(direction)).fold(f32::NEG_INFINITY, f32::max);
+    let size = core.iter().map(|corner| corner.abs().max_element()).fold(1.0, f32::max);
+    let tolerance = size * 1e-4;
+    let face = core
+        .iter()
+        .filter(|corner| corner.dot(direction) >= reach - tolerance)
+        .map(|corner| corner.dot(across))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), along| (min.min(along), max.max(along)));
+    (reach, face)
+}
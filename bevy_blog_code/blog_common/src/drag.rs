@@ -0,0 +1,172 @@
+use bevy::{color::palettes::css::WHITE, prelude::*};
+
+use crate::{
+    grid::Grid,
+    picking::{pointer_ray, PickEvent, PickShape, Pickable, PickingPlugin},
+    pointer::{PointerId, Pointers},
+};
+
+// Click and drag a `Draggable` to move it across a plane parallel to the `Grid`, or drag it with a finger.
+// A ghost shows where it will land, and it moves there when the mouse button or finger is let go.
+pub struct DragPlugin;
+
+impl Plugin for DragPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PickingPlugin>() {
+            app.add_plugins(PickingPlugin);
+        }
+        app.add_event::<DragEnded>()
+            .init_resource::<DragState>()
+            .add_systems(Update, (start_drag, drag).chain());
+    }
+}
+
+// Needs a `Pickable` too, that's what gets clicked
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Draggable {
+    pub snap: bool, // land on the grid lines, `cell_size` apart
+}
+
+// Sent when the mouse button or finger is let go. `start` and `end` are the same if it was only clicked.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragEnded {
+    pub entity: Entity,
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+struct Drag {
+    entity: Entity,
+    pointer: PointerId, // the mouse or finger doing the dragging
+    start: Vec3,      // the entity's translation when the drag started
+    grab_point: Vec3, // where the cursor ray hit it, the drag plane goes through here
+    normal: Vec3,     // the drag plane's normal, the same as the grid's
+    target: Vec3,     // where it will land
+    moved: bool,      // the pointer went further than `CLICK_DISTANCE`, until then it's only a click
+}
+
+// How many pixels the pointer has to move before a press becomes a drag.
+// A click leaves the entity where it is, even when it's off the grid and would snap.
+const CLICK_DISTANCE: f32 = 4.0;
+
+#[derive(Resource, Default)]
+struct DragState(Option<Drag>);
+
+fn start_drag(
+    mut picks: EventReader<PickEvent>,
+    draggables: Query<&Transform, With<Draggable>>,
+    grids: Query<&Grid>,
+    mut state: ResMut<DragState>,
+) {
+    for pick in picks.read() {
+        let Ok(transform) = draggables.get(pick.entity) else { continue; };
+        let normal = grids.get_single().map_or(Vec3::Y, |grid| grid.plane.axes().2);
+        state.0 = Some(Drag {
+            entity: pick.entity,
+            pointer: pick.pointer,
+            start: transform.translation,
+            grab_point: pick.hit_point,
+            normal,
+            target: transform.translation,
+            moved: false,
+        });
+    }
+}
+
+fn drag(
+    mut gizmos: Gizmos,
+    pointers: Res<Pointers>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    grids: Query<&Grid>,
+    mut draggables: Query<(&mut Transform, &Draggable, Option<&Pickable>)>,
+    mut state: ResMut<DragState>,
+    mut ended: EventWriter<DragEnded>,
+) {
+    let Some(drag) = &mut state.0 else { return; };
+    let Ok((mut transform, draggable, pickable)) = draggables.get_mut(drag.entity) else {
+        state.0 = None; // despawned mid-drag
+        return;
+    };
+
+    // The grabbed point stays under the cursor, so the entity keeps its distance from it
+    let pointer = pointers.get(drag.pointer);
+    drag.moved |= pointer.is_some_and(|pointer| pointer.position.distance(pointer.press_position) > CLICK_DISTANCE);
+    let ray = pointer.filter(|_| drag.moved).and_then(|pointer| pointer_ray(pointer.position, &cameras));
+    if let Some(ray) = ray {
+        if let Some(distance) = ray.intersect_plane(drag.grab_point, InfinitePlane3d::new(drag.normal)) {
+            let mut target = drag.start + ray.get_point(distance) - drag.grab_point;
+            if draggable.snap {
+                target = snap_to_grid(target, grids.get_single().ok());
+            }
+            drag.target = target;
+        }
+    }
+
+    if !pointer.is_some_and(|pointer| pointer.pressed) {
+        transform.translation = drag.target;
+        ended.send(DragEnded {
+            entity: drag.entity,
+            start: drag.start,
+            end: drag.target,
+        });
+        state.0 = None;
+        return;
+    }
+
+    if drag.moved {
+        draw_ghost(&mut gizmos, &transform, drag.target, drag.normal, pickable);
+    }
+}
+
+// Rounds the point to the nearest grid line crossing, leaving its height above the grid alone
+fn snap_to_grid(point: Vec3, grid: Option<&Grid>) -> Vec3 {
+    let (u, v, cell_size) = match grid {
+        Some(grid) => {
+            let (u, v, _) = grid.plane.axes();
+            (u, v, grid.cell_size)
+        }
+        None => (Vec3::X, Vec3::Z, 1.0),
+    };
+    if cell_size <= 0.0 {
+        return point;
+    }
+    let snap = |axis: Vec3| {
+        let along = point.dot(axis);
+        ((along / cell_size).round() * cell_size - along) * axis
+    };
+    point + snap(u) + snap(v)
+}
+
+// An outline of the pick shape where the entity will land, and a line to it
+fn draw_ghost(
+    gizmos: &mut Gizmos,
+    transform: &Transform,
+    target: Vec3,
+    normal: Vec3,
+    pickable: Option<&Pickable>,
+) {
+    let color = WHITE.with_alpha(0.6);
+    gizmos.line(transform.translation, target, color);
+
+    let isometry = Isometry3d::new(target, transform.rotation);
+    let scale = transform.scale.max_element();
+    match pickable.map(|pickable| pickable.shape) {
+        Some(PickShape::Sphere { radius }) => {
+            gizmos.sphere(isometry, radius * scale, color);
+        }
+        Some(PickShape::Aabb { half_extents }) => {
+            let size = half_extents * 2.0 * transform.scale;
+            let ghost = Transform::from_translation(target).with_rotation(transform.rotation).with_scale(size);
+            gizmos.cuboid(ghost, color);
+        }
+        Some(PickShape::Torus { major_radius, minor_radius }) => {
+            let torus = Torus { major_radius: major_radius * scale, minor_radius: minor_radius * scale };
+            gizmos.primitive_3d(&torus, isometry, color);
+        }
+        // meshes can have any shape, a circle on the drag plane marks the spot
+        Some(PickShape::Mesh) | None => {
+            let flat = Quat::from_rotation_arc(Vec3::Z, normal); // circles are drawn facing Z
+            gizmos.circle(Isometry3d::new(target, flat), 0.5 * scale, color);
+        }
+    }
+}
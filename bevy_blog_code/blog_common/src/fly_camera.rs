@@ -0,0 +1,180 @@
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+
+use crate::{
+    input::{action, context, scroll_notches, ActionState, EnableInputContext},
+    pointer::{PointerPlugin, Pointers},
+};
+
+// Adds the `fly_camera` system, which moves every entity with a `FlyCamera`.
+// On a touch screen two fingers look around and pinching moves forward and back.
+pub struct FlyCameraPlugin;
+
+impl Plugin for FlyCameraPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PointerPlugin>() {
+            app.add_plugins(PointerPlugin);
+        }
+        app.enable_input_context(context::FLY_CAMERA)
+            .add_systems(Update, (grab_cursor, fly_camera));
+    }
+}
+
+#[derive(Component)]
+pub struct FlyCamera {
+    pub yaw: f32,   // rotation around Y axis in radians
+    pub pitch: f32, // rotation around X axis in radians
+    pub speed: f32, // units per second, changed with the scroll wheel
+    pub min_speed: f32,
+    pub max_speed: f32,
+    pub scroll_factor: f32,     // one scroll notch multiplies or divides the speed by this
+    pub boost_multiplier: f32,  // speed multiplier while fly_boost (Shift) is held
+    pub crawl_multiplier: f32,  // speed multiplier while fly_crawl (Ctrl) is held
+    pub rotation_speed: f32,    // arrow key rotation in radians per second
+    pub mouse_sensitivity: f32, // mouse look rotation in radians per pixel
+    pub pinch_distance: f32,    // how far spreading two fingers to twice as far apart moves forward
+}
+
+impl Default for FlyCamera {
+    fn default() -> Self {
+        FlyCamera {
+            yaw: 0.0,
+            pitch: 0.0,
+            speed: 5.0,
+            min_speed: 0.5,
+            max_speed: 100.0,
+            scroll_factor: 1.2,
+            boost_multiplier: 4.0,
+            crawl_multiplier: 0.25,
+            rotation_speed: 1.5,
+            mouse_sensitivity: 0.003,
+            pinch_distance: 2.0,
+        }
+    }
+}
+
+// Hide and lock the cursor while mouse_look (right mouse button) is held, so it can't leave the window mid-look
+fn grab_cursor(
+    actions: Res<ActionState>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else { return; };
+
+    if actions.just_pressed(action::MOUSE_LOOK) {
+        window.cursor_options.grab_mode = CursorGrabMode::Locked;
+        window.cursor_options.visible = false;
+    }
+    if actions.just_released(action::MOUSE_LOOK) {
+        window.cursor_options.grab_mode = CursorGrabMode::None;
+        window.cursor_options.visible = true;
+    }
+}
+
+// WASD + QE movement, arrow keys or right mouse drag for rotation,
+// scroll wheel for speed, Shift to go faster and Ctrl to go slower.
+// These are the default bindings, the system itself only reads actions.
+// On a gamepad the left stick moves, the right stick looks and the triggers go up and down.
+pub(crate) fn fly_camera(
+    mut query: Query<(&mut Transform, &mut FlyCamera)>,
+    actions: Res<ActionState>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    pointers: Res<Pointers>,
+    time: Res<Time>,
+) {
+    let scroll = scroll_notches(&mouse_scroll);
+    let gesture = pointers.gesture();
+
+    for (mut transform, mut camera) in &mut query {
+        let rot_speed = camera.rotation_speed;
+
+        // spin on Y axis
+        if actions.pressed(action::LOOK_LEFT) {
+            camera.yaw += rot_speed * time.delta_secs();
+        }
+        if actions.pressed(action::LOOK_RIGHT) {
+            camera.yaw -= rot_speed * time.delta_secs();
+        }
+        // pitch up/down
+        if actions.pressed(action::LOOK_UP) {
+            camera.pitch += rot_speed * time.delta_secs();
+        }
+        if actions.pressed(action::LOOK_DOWN) {
+            camera.pitch -= rot_speed * time.delta_secs();
+        }
+        // gamepad look, the stick works like the arrow keys but can be pushed part way
+        let look = actions.right_stick();
+        camera.yaw -= look.x * rot_speed * time.delta_secs();
+        camera.pitch += look.y * rot_speed * time.delta_secs();
+
+        // mouse look, the mouse delta is already per frame so no delta time here
+        if actions.pressed(action::MOUSE_LOOK) {
+            camera.yaw -= mouse_motion.delta.x * camera.mouse_sensitivity;
+            camera.pitch -= mouse_motion.delta.y * camera.mouse_sensitivity;
+        }
+        // two fingers drag the view around like the right mouse button
+        if let Some(gesture) = gesture {
+            camera.yaw -= gesture.pan.x * camera.mouse_sensitivity;
+            camera.pitch -= gesture.pan.y * camera.mouse_sensitivity;
+        }
+        camera.pitch = camera.pitch.clamp(-1.54, 1.54); // clamp pitch to avoid flipping
+
+        // apply yaw and pitch rotation to the camera
+        transform.rotation =
+            Quat::from_axis_angle(Vec3::Y, camera.yaw) * Quat::from_axis_angle(Vec3::X, camera.pitch);
+
+        // scrolling up speeds the camera up, scrolling down slows it down
+        if scroll != 0.0 {
+            camera.speed = (camera.speed * camera.scroll_factor.powf(scroll))
+                .clamp(camera.min_speed, camera.max_speed);
+        }
+
+        // movement (WASD for horizontal, QE for vertical)
+        let mut direction = Vec3::ZERO;
+        if actions.pressed(action::FLY_FORWARD) {
+            direction += *transform.forward();
+        }
+        if actions.pressed(action::FLY_BACK) {
+            direction -= *transform.forward();
+        }
+        if actions.pressed(action::FLY_LEFT) {
+            direction -= *transform.right();
+        }
+        if actions.pressed(action::FLY_RIGHT) {
+            direction += *transform.right();
+        }
+        if actions.pressed(action::FLY_UP) {
+            direction += Vec3::Y;
+        }
+        if actions.pressed(action::FLY_DOWN) {
+            direction -= Vec3::Y;
+        }
+
+        // keys always move at full speed, the sticks and triggers can add a smaller push
+        let stick = actions.left_stick();
+        let analog = *transform.right() * stick.x
+            + *transform.forward() * stick.y
+            + Vec3::Y * (actions.right_trigger() - actions.left_trigger());
+        let direction = (direction.normalize_or_zero() + analog).clamp_length_max(1.0);
+
+        let mut speed = camera.speed;
+        if actions.pressed(action::FLY_BOOST) {
+            speed *= camera.boost_multiplier;
+        }
+        if actions.pressed(action::FLY_CRAWL) {
+            speed *= camera.crawl_multiplier;
+        }
+
+        transform.translation += direction * speed * time.delta_secs();
+
+        // spreading two fingers moves forward, pinching them together moves back.
+        // The pinch is already a change this frame, so it isn't scaled by the speed or the frame time.
+        if let Some(gesture) = gesture {
+            let forward = transform.forward();
+            transform.translation += forward * gesture.pinch.log2() * camera.pinch_distance;
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+// Moves every entity with a `FollowTarget` after its target, works for 2D and 3D cameras.
+pub struct FollowPlugin;
+
+impl Plugin for FollowPlugin {
+    fn build(&self, app: &mut App) {
+        // after the game has moved things, but before transforms are sent to the renderer
+        app.add_systems(PostUpdate, follow_target.before(TransformSystem::TransformPropagate));
+    }
+}
+
+#[derive(Component)]
+pub struct FollowTarget {
+    pub target: Entity,
+    pub offset: Vec3,     // where the camera sits relative to the target
+    pub smooth_time: f32, // roughly how many seconds the camera takes to catch up, 0.0 snaps
+    pub dead_zone: Vec2,  // half width and height of a box the target can move in without the camera moving
+    pub look_at: bool,    // turn to face the target, for 3D cameras
+    pub bounds: Option<Rect>, // keep a 2D camera's view inside this part of the world, like the edges of a map
+    pub velocity: Vec3,   // how fast the camera is moving right now
+}
+
+impl FollowTarget {
+    pub fn new(target: Entity) -> Self {
+        FollowTarget {
+            target,
+            offset: Vec3::ZERO,
+            smooth_time: 0.3,
+            dead_zone: Vec2::ZERO,
+            look_at: false,
+            bounds: None,
+            velocity: Vec3::ZERO,
+        }
+    }
+}
+
+// Targets are read from their local Transform, so they should not be children of other entities
+fn follow_target(
+    time: Res<Time>,
+    mut followers: Query<(&mut Transform, &mut FollowTarget, Option<&OrthographicProjection>)>,
+    targets: Query<&Transform, Without<FollowTarget>>,
+) {
+    for (mut transform, mut follow, projection) in &mut followers {
+        let Ok(target) = targets.get(follow.target) else { continue; };
+        let target = target.translation;
+
+        // The point the camera is looking after right now, and how far the target is from it,
+        // measured along the camera's own right and up directions
+        let focus = transform.translation - follow.offset;
+        let delta = target - focus;
+        let (right, up) = (*transform.right(), *transform.up());
+        let along_right = delta.dot(right);
+        let along_up = delta.dot(up);
+        let along_rest = delta - right * along_right - up * along_up;
+
+        // only the part of the move that leaves the dead zone moves the camera
+        let outside = |distance: f32, half_size: f32| distance.signum() * (distance.abs() - half_size).max(0.0);
+        let goal_focus = focus
+            + right * outside(along_right, follow.dead_zone.x)
+            + up * outside(along_up, follow.dead_zone.y)
+            + along_rest;
+        let mut goal = goal_focus + follow.offset;
+        if let (Some(bounds), Some(projection)) = (follow.bounds, projection) {
+            goal = clamp_view(goal, projection.area, bounds);
+        }
+
+        let smooth_time = follow.smooth_time;
+        let (position, velocity) = smooth_damp(
+            transform.translation,
+            goal,
+            follow.velocity,
+            smooth_time,
+            time.delta_secs(),
+        );
+        transform.translation = position;
+        follow.velocity = velocity;
+        if let (Some(bounds), Some(projection)) = (follow.bounds, projection) {
+            // the spring never overshoots the goal, this only matters when the camera starts outside
+            transform.translation = clamp_view(transform.translation, projection.area, bounds);
+        }
+
+        if follow.look_at {
+            transform.look_at(target, Vec3::Y);
+        }
+    }
+}
+
+// Moves a 2D camera so the edges of its view stay inside the bounds. `view` is the area it sees around itself.
+// When the bounds are smaller than the view, the camera sits in their middle instead.
+fn clamp_view(position: Vec3, view: Rect, bounds: Rect) -> Vec3 {
+    let clamp_axis = |value: f32, view_min: f32, view_max: f32, min: f32, max: f32| {
+        let (low, high) = (min - view_min, max - view_max);
+        if low > high {
+            (low + high) / 2.0
+        } else {
+            value.clamp(low, high)
+        }
+    };
+    Vec3::new(
+        clamp_axis(position.x, view.min.x, view.max.x, bounds.min.x, bounds.max.x),
+        clamp_axis(position.y, view.min.y, view.max.y, bounds.min.y, bounds.max.y),
+        position.z,
+    )
+}
+
+// Critically damped spring: gets to the goal as fast as it can without overshooting.
+// A long frame or a goal that jumps could still carry it past, so it stops at the goal then.
+// Returns the new position and velocity.
+fn smooth_damp(current: Vec3, goal: Vec3, velocity: Vec3, smooth_time: f32, delta: f32) -> (Vec3, Vec3) {
+    if smooth_time <= 0.0 {
+        return (goal, Vec3::ZERO);
+    }
+
+    let omega = 2.0 / smooth_time;
+    let x = omega * delta;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x); // cheap, accurate stand-in for e^-x
+
+    let change = current - goal;
+    let temp = (velocity + omega * change) * delta;
+    let velocity = (velocity - omega * temp) * decay;
+    let position = goal + (change + temp) * decay;
+
+    // it went past the goal, it was on one side of it before and is on the other side now
+    if (goal - current).dot(position - goal) > 0.0 {
+        return (goal, Vec3::ZERO);
+    }
+    (position, velocity)
+}
@@ -0,0 +1,185 @@
+use bevy::{color::palettes::css::*, prelude::*};
+
+use crate::input::{action, context, ActionState, EnableInputContext};
+
+// Adds the `grid` system. The example still spawns its own `Grid` entity.
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.enable_input_context(context::GRID).add_systems(Update, grid);
+    }
+}
+
+// Which world plane the grid lies on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GridPlane {
+    XY,
+    #[default]
+    XZ, // the floor
+    YZ,
+}
+
+impl GridPlane {
+    // The two directions the grid lines run along, and the plane's normal
+    pub fn axes(self) -> (Vec3, Vec3, Vec3) {
+        match self {
+            GridPlane::XY => (Vec3::X, Vec3::Y, Vec3::Z),
+            GridPlane::XZ => (Vec3::X, Vec3::Z, Vec3::Y),
+            GridPlane::YZ => (Vec3::Y, Vec3::Z, Vec3::X),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Grid {
+    pub enabled: bool,
+    pub plane: GridPlane,
+    pub size: i32,         // number of cells from the centre to the edge
+    pub cell_size: f32,    // distance between two minor lines
+    pub major_every: i32,  // every n-th line is a major line
+    pub minor_color: Color,
+    pub major_color: Color,
+    pub axis_colors: [Color; 3], // colours of the X, Y and Z axes
+    pub fade_distance: f32, // lines are invisible this far from the camera, 0.0 turns fading off
+    pub follow_camera: bool, // keep the grid centred under the camera so it looks infinite
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid {
+            enabled: false,
+            plane: GridPlane::XZ,
+            size: 20,
+            cell_size: 1.0,
+            major_every: 5,
+            minor_color: Color::srgb(0.3, 0.3, 0.3),
+            major_color: GREY.into(),
+            axis_colors: [RED.into(), GREEN.into(), BLUE.into()],
+            fade_distance: 20.0,
+            follow_camera: true,
+        }
+    }
+}
+
+// Draw grid and axes, toggle with the toggle_grid action (Space)
+fn grid(
+    mut gizmos: Gizmos,
+    actions: Res<ActionState>,
+    mut grid_query: Query<&mut Grid>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+) {
+    let camera_position = active_camera(&cameras).map(|(_, transform)| transform.translation());
+
+    for mut grid in &mut grid_query {
+        // toggle grid visibility
+        if actions.just_pressed(action::TOGGLE_GRID) {
+            grid.enabled = !grid.enabled;
+        }
+
+        if grid.enabled {
+            draw_grid(&mut gizmos, &grid, camera_position);
+        }
+    }
+}
+
+// The camera the grid follows: the active camera drawn first, and the biggest one if several share the same order
+pub(crate) fn active_camera<'a>(
+    cameras: &'a Query<(&Camera, &GlobalTransform)>,
+) -> Option<(&'a Camera, &'a GlobalTransform)> {
+    let area = |camera: &Camera| {
+        camera
+            .physical_viewport_size()
+            .map_or(0, |size| size.x * size.y)
+    };
+    cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .min_by(|(a, _), (b, _)| a.order.cmp(&b.order).then(area(b).cmp(&area(a))))
+}
+
+fn draw_grid(gizmos: &mut Gizmos, grid: &Grid, camera_position: Option<Vec3>) {
+    let (u, v, normal) = grid.plane.axes();
+    let cell_size = grid.cell_size.max(f32::EPSILON);
+    let major_every = grid.major_every.max(1);
+    let size = grid.size.max(1);
+    let extent = size as f32 * cell_size;
+
+    // Centre of the grid in cells. Snapping it to major lines stops the lines from sliding as the camera moves.
+    let (centre_u, centre_v) = match camera_position {
+        Some(position) if grid.follow_camera => {
+            let snap = |d: f32| (d / (cell_size * major_every as f32)).round() as i32 * major_every;
+            (snap(position.dot(u)), snap(position.dot(v)))
+        }
+        _ => (0, 0),
+    };
+    let centre = u * (centre_u as f32 * cell_size) + v * (centre_v as f32 * cell_size);
+
+    let line_color = |index: i32, along: Vec3| {
+        if index == 0 {
+            axis_color(grid, along)
+        } else if index.rem_euclid(major_every) == 0 {
+            grid.major_color
+        } else {
+            grid.minor_color
+        }
+    };
+
+    for i in -size..=size {
+        let offset = i as f32 * cell_size;
+
+        // line running along v, crossing the u axis at `offset`
+        let start = centre + u * offset - v * extent;
+        let end = centre + u * offset + v * extent;
+        draw_faded_line(gizmos, grid, start, end, line_color(centre_u + i, v), camera_position);
+
+        // line running along u, crossing the v axis at `offset`
+        let start = centre + v * offset - u * extent;
+        let end = centre + v * offset + u * extent;
+        draw_faded_line(gizmos, grid, start, end, line_color(centre_v + i, u), camera_position);
+    }
+
+    // the axis sticking out of the plane, through the origin
+    draw_faded_line(
+        gizmos,
+        grid,
+        -normal * extent,
+        normal * extent,
+        axis_color(grid, normal),
+        camera_position,
+    );
+}
+
+fn axis_color(grid: &Grid, axis: Vec3) -> Color {
+    let axis = axis.abs();
+    if axis.x >= axis.y && axis.x >= axis.z {
+        grid.axis_colors[0]
+    } else if axis.y >= axis.z {
+        grid.axis_colors[1]
+    } else {
+        grid.axis_colors[2]
+    }
+}
+
+// Draws a line in one-cell pieces so each piece can fade by its own distance to the camera
+fn draw_faded_line(
+    gizmos: &mut Gizmos,
+    grid: &Grid,
+    start: Vec3,
+    end: Vec3,
+    color: Color,
+    camera_position: Option<Vec3>,
+) {
+    let Some(camera_position) = camera_position.filter(|_| grid.fade_distance > 0.0) else {
+        gizmos.line(start, end, color);
+        return;
+    };
+
+    let steps = (start.distance(end) / grid.cell_size.max(f32::EPSILON)).ceil().max(1.0) as usize;
+    let points = (0..=steps).map(|step| {
+        let point = start.lerp(end, step as f32 / steps as f32);
+        let fade = 1.0 - point.distance(camera_position) / grid.fade_distance;
+        (point, color.with_alpha(color.alpha() * fade.clamp(0.0, 1.0)))
+    });
+    gizmos.linestrip_gradient(points);
+}
@@ -0,0 +1,105 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, SystemCursorIcon},
+    winit::cursor::CursorIcon,
+};
+
+use crate::{
+    mesh_picking::MeshRayCast,
+    picking::{cursor_ray, pick_nearest, Pickable},
+};
+
+// How much brighter a hovered object glows
+const HOVER_EMISSIVE: LinearRgba = LinearRgba::new(0.25, 0.25, 0.25, 0.0);
+
+// The pickable entity under the mouse cursor, if any
+#[derive(Resource, Default, Debug)]
+pub struct HoveredEntity(pub Option<Entity>);
+
+// Sent when the cursor moves onto a pickable entity
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoverEnter {
+    pub entity: Entity,
+}
+
+// Sent when the cursor moves off it again, or onto something in front of it
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoverLeave {
+    pub entity: Entity,
+}
+
+// Entities showing a glowing copy of their material, with the material they had before
+#[derive(Resource, Default)]
+pub(crate) struct HighlightedMaterials(Vec<Highlighted>);
+
+pub(crate) struct Highlighted {
+    entity: Entity,
+    original: Handle<StandardMaterial>,
+    copy: Handle<StandardMaterial>,
+}
+
+// Ray casts from the cursor every frame, sends enter and leave events when the closest
+// pickable under it changes, and shows the pointer cursor while there is one
+pub(crate) fn update_hover(
+    mut commands: Commands,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    pickables: Query<(Entity, &GlobalTransform, &Pickable)>,
+    mut meshes: MeshRayCast,
+    mut hovered: ResMut<HoveredEntity>,
+    mut enters: EventWriter<HoverEnter>,
+    mut leaves: EventWriter<HoverLeave>,
+) {
+    let Ok((window_entity, window)) = windows.get_single() else { return; };
+    let under_cursor = cursor_ray(window, &cameras)
+        .and_then(|ray| pick_nearest(ray, &pickables, &mut meshes))
+        .map(|hit| hit.entity);
+    if under_cursor == hovered.0 {
+        return;
+    }
+
+    if let Some(entity) = hovered.0 {
+        leaves.send(HoverLeave { entity });
+    }
+    if let Some(entity) = under_cursor {
+        enters.send(HoverEnter { entity });
+    }
+    hovered.0 = under_cursor;
+
+    let icon = if under_cursor.is_some() { SystemCursorIcon::Pointer } else { SystemCursorIcon::Default };
+    commands.entity(window_entity).insert(CursorIcon::System(icon));
+}
+
+// Makes the hovered entity and its children glow a little. Materials are shared by handle,
+// so each of them gets a glowing copy of its material while hovered, and the original back afterwards.
+pub(crate) fn highlight_hovered(
+    mut enters: EventReader<HoverEnter>,
+    mut leaves: EventReader<HoverLeave>,
+    children: Query<&Children>,
+    mut mesh_materials: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut highlighted: ResMut<HighlightedMaterials>,
+) {
+    if !leaves.is_empty() {
+        leaves.clear();
+        for Highlighted { entity, original, copy } in highlighted.0.drain(..) {
+            // unless something else gave it a new material in the meantime
+            if let Ok(mut material) = mesh_materials.get_mut(entity) {
+                if material.0 == copy {
+                    material.0 = original;
+                }
+            }
+        }
+    }
+
+    for enter in enters.read() {
+        for entity in std::iter::once(enter.entity).chain(children.iter_descendants(enter.entity)) {
+            let Ok(mut material) = mesh_materials.get_mut(entity) else { continue; };
+            let Some(mut glowing) = materials.get(&material.0).cloned() else { continue; };
+            glowing.emissive += HOVER_EMISSIVE;
+            let copy = materials.add(glowing);
+            let original = std::mem::replace(&mut material.0, copy.clone());
+            highlighted.0.push(Highlighted { entity, original, copy });
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::{BTreeMap, HashSet};
+
+use bevy::{
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent},
+        mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+        InputSystem,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+// Systems read named actions such as "toggle_grid" instead of checking a KeyCode.
+// The keys behind each action come from `assets/input.ron` in the example's folder,
+// so everyone can rebind controls without touching the code.
+// Only actions turned on with `enable_input_context` are read, see `context`.
+// Gamepad sticks and triggers are read here too, with the dead zone and response curve applied.
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputMap::load_or_default())
+            .init_resource::<ActionState>()
+            .add_systems(
+                PreUpdate,
+                (log_gamepad_connections, update_action_state).after(InputSystem),
+            );
+    }
+}
+
+// Names of all actions the examples use
+pub mod action {
+    pub const TOGGLE_GRID: &str = "toggle_grid";
+    pub const TOGGLE_FULLSCREEN: &str = "toggle_fullscreen";
+
+    pub const FLY_FORWARD: &str = "fly_forward";
+    pub const FLY_BACK: &str = "fly_back";
+    pub const FLY_LEFT: &str = "fly_left";
+    pub const FLY_RIGHT: &str = "fly_right";
+    pub const FLY_UP: &str = "fly_up";
+    pub const FLY_DOWN: &str = "fly_down";
+    pub const FLY_BOOST: &str = "fly_boost";
+    pub const FLY_CRAWL: &str = "fly_crawl";
+    pub const LOOK_LEFT: &str = "look_left";
+    pub const LOOK_RIGHT: &str = "look_right";
+    pub const LOOK_UP: &str = "look_up";
+    pub const LOOK_DOWN: &str = "look_down";
+    pub const MOUSE_LOOK: &str = "mouse_look";
+
+    pub const ORBIT_DRAG: &str = "orbit_drag";
+    pub const ORBIT_PAN: &str = "orbit_pan";
+
+    pub const PICK: &str = "pick"; // click or drag things, or drag a selection rectangle
+
+    pub const SELECT_ADD: &str = "select_add"; // hold to add to the selection
+    pub const SELECT_TOGGLE: &str = "select_toggle"; // hold to flip entities in and out of the selection
+
+    pub const BOOKMARK_SAVE: &str = "bookmark_save"; // hold while pressing a bookmark to save it
+    pub const BOOKMARKS: [&str; 9] = [
+        "bookmark_1", "bookmark_2", "bookmark_3", "bookmark_4", "bookmark_5",
+        "bookmark_6", "bookmark_7", "bookmark_8", "bookmark_9",
+    ];
+
+    pub const JIGGLE: &str = "jiggle";
+
+    pub const VOLUME_UP: &str = "volume_up";
+    pub const VOLUME_DOWN: &str = "volume_down";
+
+    pub const PLAYER_LEFT: &str = "player_left";
+    pub const PLAYER_RIGHT: &str = "player_right";
+    pub const PLAYER_UP: &str = "player_up";
+    pub const PLAYER_DOWN: &str = "player_down";
+    pub const PLAYER_DASH: &str = "player_dash"; // hold to move much faster
+
+    pub const EDITOR_TOGGLE: &str = "editor_toggle"; // switch a level editor on and off
+    pub const EDITOR_DELETE: &str = "editor_delete";
+    pub const EDITOR_COMMAND: &str = "editor_command"; // hold while pressing undo, redo, save or load
+    pub const EDITOR_UNDO: &str = "editor_undo";
+    pub const EDITOR_REDO: &str = "editor_redo";
+    pub const EDITOR_SAVE: &str = "editor_save";
+    pub const EDITOR_LOAD: &str = "editor_load";
+
+    // Actions that only change what the other controls do while they are held.
+    // These can share a key, holding Ctrl in donut_animation both slows the camera and toggles the selection.
+    pub const HELD: &[&str] = &[
+        FLY_BOOST, FLY_CRAWL, SELECT_ADD, SELECT_TOGGLE, BOOKMARK_SAVE, EDITOR_COMMAND, PLAYER_DASH,
+    ];
+}
+
+// The actions each plugin or example reads, turned on together with `enable_input_context`.
+// Actions in different contexts can share a key as long as no app turns both contexts on,
+// like the arrow keys, which look around with the fly camera and move the player in the 2D games.
+pub mod context {
+    use super::action::{BOOKMARKS as SLOTS, *};
+
+    pub const GRID: &[&str] = &[TOGGLE_GRID];
+    pub const WINDOW: &[&str] = &[TOGGLE_FULLSCREEN];
+    pub const FLY_CAMERA: &[&str] = &[
+        FLY_FORWARD, FLY_BACK, FLY_LEFT, FLY_RIGHT, FLY_UP, FLY_DOWN, FLY_BOOST, FLY_CRAWL,
+        LOOK_LEFT, LOOK_RIGHT, LOOK_UP, LOOK_DOWN, MOUSE_LOOK,
+    ];
+    pub const ORBIT_CAMERA: &[&str] = &[ORBIT_DRAG, ORBIT_PAN];
+    pub const PICKING: &[&str] = &[PICK];
+    pub const SELECTION: &[&str] = &[PICK, SELECT_ADD, SELECT_TOGGLE];
+    pub const BOOKMARKS: &[&str] = &[
+        BOOKMARK_SAVE, SLOTS[0], SLOTS[1], SLOTS[2], SLOTS[3], SLOTS[4], SLOTS[5], SLOTS[6], SLOTS[7], SLOTS[8],
+    ];
+    pub const JIGGLE: &[&str] = &[super::action::JIGGLE];
+    pub const AUDIO: &[&str] = &[VOLUME_UP, VOLUME_DOWN];
+    pub const PLAYER: &[&str] = &[PLAYER_LEFT, PLAYER_RIGHT, PLAYER_UP, PLAYER_DOWN, PLAYER_DASH];
+    pub const EDITOR: &[&str] = &[
+        EDITOR_TOGGLE, EDITOR_DELETE, EDITOR_COMMAND, EDITOR_UNDO, EDITOR_REDO, EDITOR_SAVE, EDITOR_LOAD,
+    ];
+}
+
+// Turns on the actions of a context, adding the `InputMapPlugin` if it isn't there yet
+pub trait EnableInputContext {
+    fn enable_input_context(&mut self, actions: &[&str]) -> &mut Self;
+}
+
+impl EnableInputContext for App {
+    fn enable_input_context(&mut self, actions: &[&str]) -> &mut Self {
+        if !self.is_plugin_added::<InputMapPlugin>() {
+            self.add_plugins(InputMapPlugin);
+        }
+        self.world_mut().resource_mut::<InputMap>().enable(actions);
+        self
+    }
+}
+
+// Anything that can be bound to an action
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+// Which bindings trigger which action. An action fires if any of its bindings is pressed.
+//
+// The file only has to list the actions it changes, see `assets/input_azerty.ron` for an example.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct InputMap {
+    #[serde(default)]
+    pub actions: BTreeMap<String, Vec<Binding>>,
+    #[serde(default)]
+    pub sticks: StickSettings,
+    #[serde(skip)]
+    enabled: Vec<(String, Vec<Binding>)>, // the actions that are read, with the bindings they got
+}
+
+// How raw stick and trigger values are turned into movement
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StickSettings {
+    pub deadzone: f32, // values below this are treated as zero, worn sticks never rest exactly at 0
+    pub curve: f32,    // response curve exponent, 1.0 is linear and bigger values give finer control near the centre
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        StickSettings {
+            deadzone: 0.15,
+            curve: 2.0,
+        }
+    }
+}
+
+impl StickSettings {
+    // Applies the dead zone and curve to a stick, keeping its direction
+    pub fn apply_stick(&self, stick: Vec2) -> Vec2 {
+        let length = stick.length();
+        if length == 0.0 {
+            return Vec2::ZERO;
+        }
+        stick / length * self.apply(length.min(1.0))
+    }
+
+    // Applies the dead zone and curve to a value between 0 and 1,
+    // rescaled so the output still starts at 0 right at the edge of the dead zone
+    pub fn apply(&self, value: f32) -> f32 {
+        if value <= self.deadzone {
+            return 0.0;
+        }
+        let value = ((value - self.deadzone) / (1.0 - self.deadzone)).min(1.0);
+        value.powf(self.curve.max(f32::EPSILON))
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use self::action::*;
+        use Binding::*;
+
+        let mut defaults = vec![
+            (TOGGLE_GRID, vec![Key(KeyCode::Space), Gamepad(GamepadButton::Select)]),
+            (TOGGLE_FULLSCREEN, vec![Key(KeyCode::F11), Gamepad(GamepadButton::Start)]),
+            (FLY_FORWARD, vec![Key(KeyCode::KeyW)]),
+            (FLY_BACK, vec![Key(KeyCode::KeyS)]),
+            (FLY_LEFT, vec![Key(KeyCode::KeyA)]),
+            (FLY_RIGHT, vec![Key(KeyCode::KeyD)]),
+            (FLY_UP, vec![Key(KeyCode::KeyQ)]),
+            (FLY_DOWN, vec![Key(KeyCode::KeyE)]),
+            (FLY_BOOST, vec![Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)]),
+            (FLY_CRAWL, vec![Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight)]),
+            (LOOK_LEFT, vec![Key(KeyCode::ArrowLeft)]),
+            (LOOK_RIGHT, vec![Key(KeyCode::ArrowRight)]),
+            (LOOK_UP, vec![Key(KeyCode::ArrowUp)]),
+            (LOOK_DOWN, vec![Key(KeyCode::ArrowDown)]),
+            (MOUSE_LOOK, vec![Mouse(MouseButton::Right)]),
+            (ORBIT_DRAG, vec![Mouse(MouseButton::Right)]),
+            (ORBIT_PAN, vec![Mouse(MouseButton::Middle)]),
+            (PICK, vec![Mouse(MouseButton::Left)]),
+            (SELECT_ADD, vec![Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)]),
+            (SELECT_TOGGLE, vec![Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight)]),
+            (JIGGLE, vec![Key(KeyCode::KeyB), Gamepad(GamepadButton::North)]),
+            (VOLUME_UP, vec![Key(KeyCode::Equal), Gamepad(GamepadButton::DPadUp)]),
+            (VOLUME_DOWN, vec![Key(KeyCode::Minus), Gamepad(GamepadButton::DPadDown)]),
+            (PLAYER_LEFT, vec![Key(KeyCode::ArrowLeft), Gamepad(GamepadButton::DPadLeft)]),
+            (PLAYER_RIGHT, vec![Key(KeyCode::ArrowRight), Gamepad(GamepadButton::DPadRight)]),
+            (PLAYER_UP, vec![Key(KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)]),
+            (PLAYER_DOWN, vec![Key(KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)]),
+            (PLAYER_DASH, vec![Key(KeyCode::Space), Gamepad(GamepadButton::South)]),
+            (EDITOR_TOGGLE, vec![Key(KeyCode::Tab)]),
+            (EDITOR_DELETE, vec![Key(KeyCode::Delete), Key(KeyCode::Backspace)]),
+            (EDITOR_COMMAND, vec![Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight), Key(KeyCode::SuperLeft)]),
+            (EDITOR_UNDO, vec![Key(KeyCode::KeyZ)]),
+            (EDITOR_REDO, vec![Key(KeyCode::KeyY)]),
+            (EDITOR_SAVE, vec![Key(KeyCode::KeyS)]),
+            (EDITOR_LOAD, vec![Key(KeyCode::KeyO)]),
+            (BOOKMARK_SAVE, vec![Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight)]),
+        ];
+        let digits = [
+            KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5,
+            KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+        ];
+        defaults.extend(BOOKMARKS.into_iter().zip(digits).map(|(name, key)| (name, vec![Key(key)])));
+
+        InputMap {
+            actions: defaults
+                .into_iter()
+                .map(|(name, bindings)| (name.to_string(), bindings))
+                .collect(),
+            sticks: StickSettings::default(),
+            enabled: Vec::new(),
+        }
+    }
+}
+
+impl InputMap {
+    // The file the bindings are read from, next to the example's other assets
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn path() -> std::path::PathBuf {
+        bevy::asset::io::file::FileAssetReader::get_base_path().join("assets/input.ron")
+    }
+
+    // Default bindings, with any action listed in `assets/input.ron` replaced by the file's bindings.
+    // A missing file is fine, a broken one is reported and ignored.
+    pub fn load_or_default() -> Self {
+        let mut map = InputMap::default();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = Self::path();
+            match std::fs::read_to_string(&path) {
+                Ok(text) => match ron::from_str::<InputMap>(&text) {
+                    Ok(file) => {
+                        info!("Loaded input bindings from {}", path.display());
+                        map.actions.extend(file.actions);
+                        map.sticks = file.sticks;
+                    }
+                    Err(err) => error!("Could not read {}: {err}, using the default bindings", path.display()),
+                },
+                Err(_) => info!("No {} found, using the default bindings", path.display()),
+            }
+        }
+
+        map
+    }
+
+    // Starts reading these actions. A binding already used by an action that was turned on earlier
+    // is left out with a warning, so one key never sets off two actions in the same app.
+    // Only `action::HELD` actions may share a key with each other.
+    pub fn enable(&mut self, actions: &[&str]) {
+        for &action in actions {
+            if self.enabled.iter().any(|(name, _)| name == action) {
+                continue;
+            }
+            let held = action::HELD.contains(&action);
+            let mut bindings = self.actions.get(action).cloned().unwrap_or_default();
+            bindings.retain(|binding| {
+                let taken = self.enabled.iter().find(|(other, used)| {
+                    used.contains(binding) && !(held && action::HELD.contains(&other.as_str()))
+                });
+                if let Some((other, _)) = taken {
+                    warn!("{binding:?} is bound to both {other} and {action}, only {other} will use it");
+                }
+                taken.is_none()
+            });
+            self.enabled.push((action.to_string(), bindings));
+        }
+    }
+
+    // What sets off the action in this app, empty if it isn't turned on
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.enabled.iter().find(|(name, _)| name == action).map_or(&[], |(_, bindings)| bindings)
+    }
+}
+
+// What each action is doing this frame, plus the analog gamepad inputs.
+// With several gamepads connected, the one pushed furthest wins.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<String>,
+    just_pressed: HashSet<String>,
+    just_released: HashSet<String>,
+    left_stick: Vec2,
+    right_stick: Vec2,
+    left_trigger: f32,
+    right_trigger: f32,
+}
+
+impl ActionState {
+    // Left stick, x to the right and y forward, each between -1 and 1
+    pub fn left_stick(&self) -> Vec2 {
+        self.left_stick
+    }
+
+    pub fn right_stick(&self) -> Vec2 {
+        self.right_stick
+    }
+
+    // How far the triggers are pulled, between 0 and 1
+    pub fn left_trigger(&self) -> f32 {
+        self.left_trigger
+    }
+
+    pub fn right_trigger(&self) -> f32 {
+        self.right_trigger
+    }
+
+    pub fn pressed(&self, action: &str) -> bool {
+        self.pressed.contains(action)
+    }
+
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.just_pressed.contains(action)
+    }
+
+    pub fn just_released(&self, action: &str) -> bool {
+        self.just_released.contains(action)
+    }
+}
+
+pub(crate) fn update_action_state(
+    input_map: Res<InputMap>,
+    mut state: ResMut<ActionState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let pressed = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.pressed(key),
+        Binding::Mouse(button) => mouse_buttons.pressed(button),
+        Binding::Gamepad(button) => gamepads.iter().any(|gamepad| gamepad.pressed(button)),
+    };
+    let just_pressed = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.just_pressed(key),
+        Binding::Mouse(button) => mouse_buttons.just_pressed(button),
+        Binding::Gamepad(button) => gamepads.iter().any(|gamepad| gamepad.just_pressed(button)),
+    };
+
+    let state = &mut *state;
+    state.just_pressed.clear();
+    state.just_released.clear();
+
+    let sticks = input_map.sticks;
+    let strongest_stick = |stick: fn(&Gamepad) -> Vec2| {
+        gamepads
+            .iter()
+            .map(|gamepad| sticks.apply_stick(stick(gamepad)))
+            .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+            .unwrap_or_default()
+    };
+    let strongest_trigger = |trigger: GamepadButton| {
+        gamepads
+            .iter()
+            .map(|gamepad| sticks.apply(gamepad.get(trigger).unwrap_or(0.0)))
+            .fold(0.0, f32::max)
+    };
+    state.left_stick = strongest_stick(Gamepad::left_stick);
+    state.right_stick = strongest_stick(Gamepad::right_stick);
+    state.left_trigger = strongest_trigger(GamepadButton::LeftTrigger2);
+    state.right_trigger = strongest_trigger(GamepadButton::RightTrigger2);
+
+    for (action, bindings) in &input_map.enabled {
+        let was_pressed = state.pressed.contains(action);
+        let is_pressed = bindings.iter().any(pressed);
+
+        if bindings.iter().any(just_pressed) {
+            state.just_pressed.insert(action.clone());
+        }
+        if was_pressed && !is_pressed {
+            state.just_released.insert(action.clone());
+        }
+        if is_pressed {
+            state.pressed.insert(action.clone());
+        } else {
+            state.pressed.remove(action);
+        }
+    }
+}
+
+// Scroll wheel notches this frame, touchpads report pixels instead of lines
+pub(crate) fn scroll_notches(mouse_scroll: &AccumulatedMouseScroll) -> f32 {
+    match mouse_scroll.unit {
+        MouseScrollUnit::Line => mouse_scroll.delta.y,
+        MouseScrollUnit::Pixel => mouse_scroll.delta.y / 16.0,
+    }
+}
+
+// Gamepads can be plugged in and out while an example runs, every system sees them straight away.
+// This just reports it so it's clear why the controls changed.
+fn log_gamepad_connections(mut events: EventReader<GamepadConnectionEvent>) {
+    for event in events.read() {
+        match &event.connection {
+            GamepadConnection::Connected { name, .. } => info!("Gamepad connected: {name}"),
+            GamepadConnection::Disconnected => info!("Gamepad disconnected"),
+        }
+    }
+}
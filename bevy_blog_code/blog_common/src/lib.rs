@@ -0,0 +1,48 @@
+// Code shared by the blog examples.
+//
+// Each example used to carry its own copy of the grid and fly camera, so a fix
+// had to be made in every one of them. Now the examples add these plugins instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod asset_watch;
+pub mod bookmarks;
+pub mod collision;
+pub mod drag;
+pub mod fly_camera;
+pub mod follow;
+pub mod grid;
+pub mod hover;
+pub mod input;
+pub mod mesh_picking;
+pub mod orbit_camera;
+pub mod picking;
+pub mod pointer;
+pub mod random;
+pub mod selection;
+pub mod spatial_hash;
+pub mod test_support;
+pub mod tilemap;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use asset_watch::{AssetWatch, AssetWatchPlugin};
+pub use bookmarks::{CameraBookmarks, CameraBookmarksPlugin};
+pub use collision::{
+    collide, move_and_slide, time_of_impact, Collider, CollisionEnded, CollisionPlugin, CollisionStarted, Collisions,
+    Contact, Slide,
+};
+pub use drag::{DragEnded, DragPlugin, Draggable};
+pub use fly_camera::{FlyCamera, FlyCameraPlugin};
+pub use follow::{FollowPlugin, FollowTarget};
+pub use grid::{Grid, GridPlane, GridPlugin};
+pub use hover::{HoverEnter, HoverLeave, HoveredEntity};
+pub use input::{action, context, ActionState, Binding, EnableInputContext, InputMap, InputMapPlugin};
+pub use orbit_camera::{OrbitCamera, OrbitCameraPlugin, OrbitTarget};
+pub use mesh_picking::MeshHit;
+pub use picking::{log_picks, PickEvent, PickShape, Pickable, PickingPlugin};
+pub use pointer::{PointerAction, PointerEvent, PointerId, PointerPlugin, Pointers, TwoFingerGesture};
+pub use random::Random;
+pub use selection::{Selectable, Selected, SelectionBlocked, SelectionPlugin};
+pub use spatial_hash::SpatialHash;
+pub use tilemap::{
+    Tile, TileSlide, Tilemap, TilemapError, TilemapLoadError, TilemapLoader, TilemapPlugin, LOADED_TILE_SIZE,
+};
@@ -0,0 +1,250 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    render::mesh::PrimitiveTopology,
+    utils::HashMap,
+};
+
+// A bounding volume hierarchy: a tree of boxes around a mesh's triangles.
+// A ray only has to test the triangles in the boxes it passes through, instead of all of them.
+pub struct MeshBvh {
+    triangles: Vec<[Vec3; 3]>,
+    nodes: Vec<BvhNode>,
+    order: Vec<usize>, // triangle indices, sorted so every leaf owns one run of them
+}
+
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    start: usize, // first entry of `order` in this box
+    count: usize, // how many triangles a leaf has, 0 for a box that holds two more boxes
+    right: usize, // the second child's node index, the first child always comes right after its parent
+}
+
+const LEAF_SIZE: usize = 4;
+
+impl MeshBvh {
+    pub fn new(triangles: Vec<[Vec3; 3]>) -> Self {
+        let centroids: Vec<Vec3> = triangles.iter().map(|[a, b, c]| (*a + *b + *c) / 3.0).collect();
+        let mut bvh = MeshBvh {
+            order: (0..triangles.len()).collect(),
+            triangles,
+            nodes: Vec::new(),
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.build(&centroids, 0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    // Only triangle lists can be picked, which is what glTF files and bevy's shapes use
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+        let corner = |index: usize| positions.get(index).copied().map(Vec3::from);
+
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => (0..positions.len()).collect(),
+        };
+        let triangles = indices
+            .chunks_exact(3)
+            .filter_map(|corners| Some([corner(corners[0])?, corner(corners[1])?, corner(corners[2])?]))
+            .collect();
+        Some(MeshBvh::new(triangles))
+    }
+
+    // Returns the node index of the new box
+    fn build(&mut self, centroids: &[Vec3], start: usize, end: usize) -> usize {
+        let (mut min, mut max) = (Vec3::INFINITY, Vec3::NEG_INFINITY);
+        let (mut centre_min, mut centre_max) = (Vec3::INFINITY, Vec3::NEG_INFINITY);
+        for &triangle in &self.order[start..end] {
+            for corner in self.triangles[triangle] {
+                min = min.min(corner);
+                max = max.max(corner);
+            }
+            centre_min = centre_min.min(centroids[triangle]);
+            centre_max = centre_max.max(centroids[triangle]);
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode { min, max, start, count: end - start, right: 0 });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        // split the triangles in half along the direction they are most spread out in
+        let spread = centre_max - centre_min;
+        let axis = if spread.x >= spread.y && spread.x >= spread.z {
+            0
+        } else if spread.y >= spread.z {
+            1
+        } else {
+            2
+        };
+        let middle = (start + end) / 2;
+        self.order[start..end].select_nth_unstable_by(middle - start, |&a, &b| {
+            centroids[a][axis].total_cmp(&centroids[b][axis])
+        });
+
+        self.build(centroids, start, middle);
+        let right = self.build(centroids, middle, end);
+        self.nodes[index].count = 0;
+        self.nodes[index].right = right;
+        index
+    }
+
+    // Distance to the closest triangle the ray hits, the triangle's index in the mesh
+    // and its normal, turned to face the ray. Works in the mesh's own space.
+    pub fn ray_cast(&self, origin: Vec3, direction: Vec3) -> Option<(f32, usize, Vec3)> {
+        let inverse_direction = direction.recip();
+        let mut closest: Option<(f32, usize)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let limit = closest.map_or(f32::INFINITY, |(distance, _)| distance);
+            if !ray_hits_box(origin, inverse_direction, node.min, node.max, limit) {
+                continue;
+            }
+
+            if node.count == 0 {
+                stack.push(node.right);
+                stack.push(index + 1);
+                continue;
+            }
+            for &triangle in &self.order[node.start..node.start + node.count] {
+                if let Some(distance) = ray_triangle(origin, direction, self.triangles[triangle]) {
+                    if closest.is_none_or(|(best, _)| distance < best) {
+                        closest = Some((distance, triangle));
+                    }
+                }
+            }
+        }
+
+        let (distance, triangle) = closest?;
+        let [a, b, c] = self.triangles[triangle];
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        let normal = if normal.dot(direction) > 0.0 { -normal } else { normal };
+        Some((distance, triangle, normal))
+    }
+}
+
+// Slab test against a box, ignoring boxes further away than `limit`
+fn ray_hits_box(origin: Vec3, inverse_direction: Vec3, min: Vec3, max: Vec3, limit: f32) -> bool {
+    let (mut enter, mut exit) = (0.0f32, limit);
+    for axis in 0..3 {
+        // the ray doesn't move along this axis, so it has to start between the two sides.
+        // Multiplying by the infinite inverse would give 0 * inf = NaN for a ray right on a side.
+        if inverse_direction[axis].is_infinite() {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return false;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - origin[axis]) * inverse_direction[axis];
+        let t2 = (max[axis] - origin[axis]) * inverse_direction[axis];
+        enter = enter.max(t1.min(t2));
+        exit = exit.min(t1.max(t2));
+    }
+    enter <= exit
+}
+
+// Möller–Trumbore: distance along the ray to the triangle, from either side
+fn ray_triangle(origin: Vec3, direction: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < 1e-12 {
+        return None; // the ray runs along the triangle
+    }
+    let inverse = 1.0 / determinant;
+    let to_origin = origin - a;
+    let u = to_origin.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(edge1);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge2.dot(q) * inverse;
+    (distance >= 0.0).then_some(distance)
+}
+
+// BVHs for every mesh that has been picked so far, built the first time a ray needs one
+#[derive(Resource, Default)]
+pub struct MeshBvhCache {
+    bvhs: HashMap<AssetId<Mesh>, Option<MeshBvh>>, // None for meshes that can't be picked
+}
+
+// Rebuild a mesh's BVH when the mesh changes and forget it when the mesh is gone
+pub(crate) fn refresh_mesh_bvhs(
+    mut events: EventReader<AssetEvent<Mesh>>,
+    meshes: Res<Assets<Mesh>>,
+    mut cache: ResMut<MeshBvhCache>,
+) {
+    for event in events.read() {
+        match *event {
+            AssetEvent::Modified { id } if cache.bvhs.contains_key(&id) => {
+                let bvh = meshes.get(id).and_then(MeshBvh::from_mesh);
+                cache.bvhs.insert(id, bvh);
+            }
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                cache.bvhs.remove(&id);
+            }
+            _ => {}
+        }
+    }
+}
+
+// The mesh entity and triangle a ray hit, for `PickShape::Mesh`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MeshHit {
+    pub entity: Entity,  // the entity with the `Mesh3d`, a child of the picked entity for glTF scenes
+    pub triangle: usize, // index of the triangle in that mesh, the same order as its index buffer
+}
+
+// Everything needed to ray cast against real meshes
+#[derive(SystemParam)]
+pub struct MeshRayCast<'w, 's> {
+    children: Query<'w, 's, &'static Children>,
+    mesh_entities: Query<'w, 's, (&'static Mesh3d, &'static GlobalTransform)>,
+    meshes: Res<'w, Assets<Mesh>>,
+    cache: ResMut<'w, MeshBvhCache>,
+}
+
+impl MeshRayCast<'_, '_> {
+    // The closest hit on the entity's own mesh or any mesh below it, like the parts of a glTF scene.
+    // Returns the distance, the world space normal and what was hit.
+    pub fn ray_cast(&mut self, ray: Ray3d, root: Entity) -> Option<(f32, Vec3, MeshHit)> {
+        let mut closest: Option<(f32, Vec3, MeshHit)> = None;
+
+        for entity in std::iter::once(root).chain(self.children.iter_descendants(root)) {
+            let Ok((mesh, transform)) = self.mesh_entities.get(entity) else { continue; };
+            if !self.cache.bvhs.contains_key(&mesh.id()) {
+                let Some(asset) = self.meshes.get(mesh.id()) else { continue; }; // still loading
+                self.cache.bvhs.insert(mesh.id(), MeshBvh::from_mesh(asset));
+            }
+            let Some(Some(bvh)) = self.cache.bvhs.get(&mesh.id()) else { continue; };
+
+            let hit = crate::picking::ray_cast_in_local_space(ray, transform, |origin, direction| {
+                let (distance, triangle, normal) = bvh.ray_cast(origin, direction)?;
+                Some((distance, normal, MeshHit { entity, triangle }))
+            });
+            if let Some(hit) = hit {
+                if closest.is_none_or(|(best, ..)| hit.0 < best) {
+                    closest = Some(hit);
+                }
+            }
+        }
+        closest
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+};
+
+use crate::{
+    input::{action, context, scroll_notches, ActionState, EnableInputContext},
+    pointer::{PointerPlugin, Pointers},
+};
+
+// Adds the `orbit_camera` system: right drag to orbit, scroll to zoom, middle drag to pan.
+// On a touch screen one finger orbits, two fingers pan, pinching zooms and twisting turns around the target.
+// Without input the camera slowly spins around its target on its own.
+pub struct OrbitCameraPlugin;
+
+impl Plugin for OrbitCameraPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PointerPlugin>() {
+            app.add_plugins(PointerPlugin);
+        }
+        app.enable_input_context(context::ORBIT_CAMERA).add_systems(Update, orbit_camera);
+    }
+}
+
+// What the camera circles around
+#[derive(Clone, Copy, Debug)]
+pub enum OrbitTarget {
+    Point(Vec3),
+    Entity(Entity), // follows the entity if it moves
+}
+
+#[derive(Component)]
+pub struct OrbitCamera {
+    pub target: OrbitTarget,
+    pub pan: Vec3,    // offset from the target, moved with middle drag
+    pub angle: f32,   // rotation around the target in radians
+    pub pitch: f32,   // height angle above the target in radians
+    pub radius: f32,  // distance from the target, changed with the scroll wheel
+    pub min_radius: f32,
+    pub max_radius: f32,
+    pub speed: f32,             // auto-rotate speed in radians per second
+    pub sensitivity: f32,       // orbit rotation in radians per pixel dragged
+    pub zoom_factor: f32,       // one scroll notch multiplies or divides the radius by this
+    pub pan_sensitivity: f32,   // pan distance per pixel, as a fraction of the radius
+    pub resume_after: f32,      // seconds without input before auto-rotate starts again
+    pub idle: f32,              // seconds since the last input
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        OrbitCamera {
+            target: OrbitTarget::Point(Vec3::ZERO),
+            pan: Vec3::ZERO,
+            angle: 0.0,
+            pitch: 0.54, // about 3 units up at a radius of 5
+            radius: 5.0,
+            min_radius: 1.5,
+            max_radius: 30.0,
+            speed: 0.5,
+            sensitivity: 0.005,
+            zoom_factor: 1.1,
+            pan_sensitivity: 0.002,
+            resume_after: 3.0,
+            idle: f32::INFINITY, // start spinning straight away
+        }
+    }
+}
+
+fn orbit_camera(
+    mut query: Query<(&mut Transform, &mut OrbitCamera)>,
+    targets: Query<&GlobalTransform>,
+    actions: Res<ActionState>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    pointers: Res<Pointers>,
+    time: Res<Time>,
+) {
+    let scroll = scroll_notches(&mouse_scroll);
+    let mut fingers = pointers.touches();
+    let one_finger = match (fingers.next(), fingers.next()) {
+        (Some(finger), None) => Some(finger.delta),
+        _ => None,
+    };
+    let gesture = pointers.gesture();
+
+    for (mut transform, mut orbit) in &mut query {
+        let mut had_input = false;
+
+        // right drag orbits around the target
+        if actions.pressed(action::ORBIT_DRAG) {
+            orbit.angle += mouse_motion.delta.x * orbit.sensitivity;
+            orbit.pitch += mouse_motion.delta.y * orbit.sensitivity;
+            had_input = true;
+        }
+        // one finger works the same
+        if let Some(delta) = one_finger {
+            orbit.angle += delta.x * orbit.sensitivity;
+            orbit.pitch += delta.y * orbit.sensitivity;
+            had_input = true;
+        }
+        orbit.pitch = orbit.pitch.clamp(-1.5, 1.5); // stop just short of straight up or down
+
+        // middle drag slides the point we look at, sideways and up/down on the screen
+        if actions.pressed(action::ORBIT_PAN) {
+            let scale = orbit.radius * orbit.pan_sensitivity;
+            let pan = (*transform.right() * -mouse_motion.delta.x + *transform.up() * mouse_motion.delta.y) * scale;
+            orbit.pan += pan;
+            had_input = true;
+        }
+
+        // two fingers pan like the middle button, pinch to zoom and twist to turn around the target
+        if let Some(gesture) = gesture {
+            let scale = orbit.radius * orbit.pan_sensitivity;
+            let pan = (*transform.right() * -gesture.pan.x + *transform.up() * gesture.pan.y) * scale;
+            orbit.pan += pan;
+            orbit.radius = (orbit.radius / gesture.pinch).clamp(orbit.min_radius, orbit.max_radius);
+            orbit.angle += gesture.rotation;
+            had_input = true;
+        }
+
+        // scrolling up moves closer, scrolling down moves away
+        if scroll != 0.0 {
+            orbit.radius =
+                (orbit.radius / orbit.zoom_factor.powf(scroll)).clamp(orbit.min_radius, orbit.max_radius);
+            had_input = true;
+        }
+
+        if had_input {
+            orbit.idle = 0.0;
+        } else {
+            orbit.idle += time.delta_secs();
+        }
+        if orbit.idle >= orbit.resume_after {
+            orbit.angle += orbit.speed * time.delta_secs(); // Update angle
+        }
+
+        let target = match orbit.target {
+            OrbitTarget::Point(point) => point,
+            OrbitTarget::Entity(entity) => match targets.get(entity) {
+                Ok(target) => target.translation(),
+                Err(_) => continue, // the target is gone, stay where we are
+            },
+        };
+        let focus = target + orbit.pan;
+
+        let x = orbit.radius * orbit.pitch.cos() * orbit.angle.cos();
+        let y = orbit.radius * orbit.pitch.sin();
+        let z = orbit.radius * orbit.pitch.cos() * orbit.angle.sin();
+        transform.translation = focus + Vec3::new(x, y, z);
+        transform.look_at(focus, Vec3::Y);
+    }
+}
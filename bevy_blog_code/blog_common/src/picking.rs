@@ -0,0 +1,350 @@
+use bevy::prelude::*;
+
+use crate::{
+    hover::{highlight_hovered, update_hover, HighlightedMaterials, HoverEnter, HoverLeave, HoveredEntity},
+    mesh_picking::{refresh_mesh_bvhs, MeshBvhCache, MeshHit, MeshRayCast},
+    pointer::{PointerAction, PointerEvent, PointerId, PointerPlugin},
+};
+
+// Click or tap on anything with a `Pickable` to get a `PickEvent` for it.
+// Only the closest entity under the cursor gets an event, so clicks don't go through things.
+// Pickables under the cursor also glow, turn the cursor into a pointer and send `HoverEnter` and `HoverLeave`.
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PointerPlugin>() {
+            app.add_plugins(PointerPlugin);
+        }
+        app.add_event::<PickEvent>()
+            .add_event::<HoverEnter>()
+            .add_event::<HoverLeave>()
+            .init_resource::<MeshBvhCache>()
+            .init_resource::<HoveredEntity>()
+            .init_resource::<HighlightedMaterials>()
+            .add_systems(
+                Update,
+                (refresh_mesh_bvhs, pick_on_click, update_hover, highlight_hovered).chain(),
+            );
+    }
+}
+
+// The shape that can be clicked, in the entity's own space, so it moves, turns and scales with the entity
+#[derive(Clone, Copy, Debug)]
+pub enum PickShape {
+    Sphere { radius: f32 },
+    Aabb { half_extents: Vec3 }, // a box, turned along with the entity
+    Torus { major_radius: f32, minor_radius: f32 }, // a donut lying flat around the Y axis, like bevy's Torus mesh
+    Mesh, // the real triangles of the entity's mesh and every mesh below it, for glTF scenes
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Pickable {
+    pub shape: PickShape,
+}
+
+// Sent when the pick action (left click) or a finger touching the screen hits something
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PickEvent {
+    pub entity: Entity,
+    pub pointer: PointerId, // the mouse or finger that picked it
+    pub hit_point: Vec3, // where the ray touched the shape, in world space
+    pub normal: Vec3,    // which way the surface faces there, in world space
+    pub distance: f32,   // from the camera to the hit point
+    pub mesh_hit: Option<MeshHit>, // the exact mesh and triangle, for `PickShape::Mesh`
+}
+
+fn pick_on_click(
+    mut pointer_events: EventReader<PointerEvent>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    pickables: Query<(Entity, &GlobalTransform, &Pickable)>,
+    mut meshes: MeshRayCast,
+    mut events: EventWriter<PickEvent>,
+) {
+    for pointer in pointer_events.read() {
+        if pointer.action != PointerAction::Press {
+            continue;
+        }
+        let Some(ray) = pointer_ray(pointer.position, &cameras) else { continue; };
+        if let Some(hit) = pick_nearest(ray, &pickables, &mut meshes) {
+            events.send(PickEvent { pointer: pointer.id, ..hit });
+        }
+    }
+}
+
+// Log what was clicked and where. Works with split screens too, since the ray comes from the camera under the cursor.
+pub fn log_picks(mut picks: EventReader<PickEvent>, names: Query<&Name>) {
+    for pick in picks.read() {
+        let name = names.get(pick.entity).map_or("something", |name| name.as_str());
+        let point = pick.hit_point;
+        info!("Picked {name} at ({:.2}, {:.2}, {:.2})", point.x, point.y, point.z);
+    }
+}
+
+// The ray through the mouse cursor, from whichever camera draws the spot under it
+pub fn cursor_ray(window: &Window, cameras: &Query<(&Camera, &GlobalTransform)>) -> Option<Ray3d> {
+    pointer_ray(window.cursor_position()?, cameras)
+}
+
+// The same for any spot in the window, such as where a finger touches it
+pub fn pointer_ray(position: Vec2, cameras: &Query<(&Camera, &GlobalTransform)>) -> Option<Ray3d> {
+    let (camera, camera_transform, viewport_position) = camera_under_cursor(position, cameras)?;
+    camera.viewport_to_world(camera_transform, viewport_position).ok()
+}
+
+// The camera whose viewport contains the cursor, and the cursor's position inside that viewport.
+// With split screens or a picture-in-picture view several can overlap: the camera drawn last wins,
+// which is the highest order, and the smallest viewport if they share an order, because that's the one
+// sitting on top of the bigger picture.
+pub fn camera_under_cursor<'a>(
+    cursor: Vec2,
+    cameras: &'a Query<(&Camera, &GlobalTransform)>,
+) -> Option<(&'a Camera, &'a GlobalTransform, Vec2)> {
+    let area = |rect: &Rect| rect.width() * rect.height();
+    cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .filter_map(|(camera, transform)| {
+            let viewport = camera.logical_viewport_rect()?;
+            viewport.contains(cursor).then_some((camera, transform, viewport))
+        })
+        .max_by(|a, b| a.0.order.cmp(&b.0.order).then(area(&b.2).total_cmp(&area(&a.2))))
+        .map(|(camera, transform, viewport)| (camera, transform, cursor - viewport.min))
+}
+
+// The closest of the pickables the ray hits, if any
+pub fn pick_nearest<'a>(
+    ray: Ray3d,
+    pickables: impl IntoIterator<Item = (Entity, &'a GlobalTransform, &'a Pickable)>,
+    meshes: &mut MeshRayCast,
+) -> Option<PickEvent> {
+    let mut closest: Option<PickEvent> = None;
+    for (entity, transform, pickable) in pickables {
+        let hit = match pickable.shape {
+            PickShape::Mesh => meshes
+                .ray_cast(ray, entity)
+                .map(|(distance, normal, mesh_hit)| (distance, normal, Some(mesh_hit))),
+            shape => ray_cast(ray, transform, &shape).map(|(distance, normal)| (distance, normal, None)),
+        };
+        let Some((distance, normal, mesh_hit)) = hit else { continue; };
+        if closest.is_none_or(|closest| distance < closest.distance) {
+            closest = Some(PickEvent {
+                entity,
+                pointer: PointerId::Mouse, // `pick_on_click` fills in the real one
+                hit_point: ray.get_point(distance),
+                normal,
+                distance,
+                mesh_hit,
+            });
+        }
+    }
+    closest
+}
+
+// Distance along the ray to the shape and the world space normal where it was hit.
+// `PickShape::Mesh` needs the mesh assets, use `MeshRayCast` for those.
+pub fn ray_cast(ray: Ray3d, transform: &GlobalTransform, shape: &PickShape) -> Option<(f32, Vec3)> {
+    ray_cast_in_local_space(ray, transform, |origin, direction| {
+        let (distance, normal) = shape.ray_cast_local(origin, direction)?;
+        Some((distance, normal, ()))
+    })
+    .map(|(distance, normal, ())| (distance, normal))
+}
+
+// Moves the ray into the entity's own space, where the shape sits at the origin with no rotation or scale,
+// runs `cast` with a unit direction there, and moves the distance and normal it finds back to world space
+pub(crate) fn ray_cast_in_local_space<T>(
+    ray: Ray3d,
+    transform: &GlobalTransform,
+    cast: impl FnOnce(Vec3, Vec3) -> Option<(f32, Vec3, T)>,
+) -> Option<(f32, Vec3, T)> {
+    let to_local = transform.affine().inverse();
+    let origin = to_local.transform_point3(ray.origin);
+    let direction = to_local.transform_vector3(*ray.direction);
+    let stretch = direction.length(); // how much the entity's scale changed the ray's length
+    if !stretch.is_finite() || stretch <= f32::EPSILON {
+        return None; // scaled down to nothing
+    }
+
+    let (distance, normal, extra) = cast(origin, direction / stretch)?;
+
+    // normals go through the inverse transpose, so they stay square to a stretched surface
+    let normal = to_local.matrix3.transpose().mul_vec3(normal).normalize_or_zero();
+    Some((distance / stretch, normal, extra))
+}
+
+impl PickShape {
+    // Ray cast with a unit direction in the shape's own space. Returns the distance and the normal.
+    // Always misses for `PickShape::Mesh`, which has no shape of its own.
+    pub fn ray_cast_local(&self, origin: Vec3, direction: Vec3) -> Option<(f32, Vec3)> {
+        match *self {
+            PickShape::Mesh => None,
+            PickShape::Sphere { radius } => {
+                let (near, far) = ray_sphere(origin, direction, radius)?;
+                let t = if near >= 0.0 { near } else { far }; // from inside, the ray hits the far side
+                (t >= 0.0).then(|| (t, (origin + direction * t).normalize_or_zero()))
+            }
+            PickShape::Aabb { half_extents } => {
+                // slab test: where the ray enters and leaves each pair of faces
+                let t1 = (-half_extents - origin) / direction;
+                let t2 = (half_extents - origin) / direction;
+                let enter = t1.min(t2).max_element();
+                let exit = t1.max(t2).min_element();
+                if enter > exit || exit < 0.0 {
+                    return None;
+                }
+                let t = if enter >= 0.0 { enter } else { exit };
+
+                // the face we hit is the one the point is furthest out towards
+                let point = (origin + direction * t) / half_extents;
+                let size = point.abs();
+                let normal = if size.x >= size.y && size.x >= size.z {
+                    Vec3::X * point.x.signum()
+                } else if size.y >= size.z {
+                    Vec3::Y * point.y.signum()
+                } else {
+                    Vec3::Z * point.z.signum()
+                };
+                Some((t, normal))
+            }
+            PickShape::Torus { major_radius, minor_radius } => {
+                let t = ray_torus(origin, direction, major_radius, minor_radius)?;
+                let point = origin + direction * t;
+                let ring = Vec3::new(point.x, 0.0, point.z).normalize_or_zero() * major_radius;
+                Some((t, (point - ring).normalize_or_zero()))
+            }
+        }
+    }
+}
+
+// Where a ray with a unit direction enters and leaves a sphere at the origin
+fn ray_sphere(origin: Vec3, direction: Vec3, radius: f32) -> Option<(f32, f32)> {
+    let b = origin.dot(direction);
+    let c = origin.length_squared() - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    Some((-b - root, -b + root))
+}
+
+// Distance along a ray with a unit direction to a torus lying flat around the Y axis at the origin.
+// A point is on the torus when (|p|² + R² - r²)² = 4R²(x² + z²), with R the major and r the minor radius.
+// Putting the ray's points o + t·d into that gives a quartic in t, which is solved exactly.
+pub fn ray_torus(origin: Vec3, direction: Vec3, major_radius: f32, minor_radius: f32) -> Option<f32> {
+    // Jump to just before the ray enters the bounding sphere first. A start point close to the torus
+    // keeps the quartic's numbers small, and so its rounding errors too. Stopping a little short
+    // keeps a hit right where the ray enters from coming out as a tiny negative distance.
+    let (near, _) = ray_sphere(origin, direction, major_radius + minor_radius)?;
+    let skip = (near - minor_radius).max(0.0);
+
+    // f64, because a quartic squares and cubes everything, and f32 runs out of digits quickly
+    let o = (origin + direction * skip).as_dvec3();
+    let d = direction.as_dvec3().normalize();
+    let (big, small) = (major_radius as f64, minor_radius as f64);
+
+    let n = o.dot(d);
+    let k = o.length_squared() + big * big - small * small;
+    let four_r2 = 4.0 * big * big;
+    let quartic = [
+        k * k - four_r2 * (o.x * o.x + o.z * o.z),                 // t⁰
+        4.0 * n * k - 2.0 * four_r2 * (o.x * d.x + o.z * d.z),     // t¹
+        4.0 * n * n + 2.0 * k - four_r2 * (d.x * d.x + d.z * d.z), // t²
+        4.0 * n,                                                   // t³, and t⁴ is 1
+    ];
+
+    solve_quartic(quartic)
+        .into_iter()
+        .map(|t| polish_root(quartic, t))
+        .filter(|&t| t >= -1e-9) // a tiny bit behind the start is rounding, not a hit behind the camera
+        .min_by(f64::total_cmp)
+        .map(|t| skip + t.max(0.0) as f32)
+}
+
+// Real roots of x⁴ + c3·x³ + c2·x² + c1·x + c0, with Ferrari's method
+fn solve_quartic([c0, c1, c2, c3]: [f64; 4]) -> Vec<f64> {
+    // x = y - c3/4 gets rid of the cubic term: y⁴ + p·y² + q·y + r = 0
+    let shift = c3 / 4.0;
+    let c3_squared = c3 * c3;
+    let p = c2 - 3.0 / 8.0 * c3_squared;
+    let q = c1 - c3 * c2 / 2.0 + c3_squared * c3 / 8.0;
+    let r = c0 - c3 * c1 / 4.0 + c3_squared * c2 / 16.0 - 3.0 / 256.0 * c3_squared * c3_squared;
+
+    // Pick m so that (y² + p/2 + m)² - 2m·(y - q/4m)² is the same quartic. The difference of two squares
+    // then splits it into two quadratics. A good m is a root of m³ + p·m² + (p²/4 - r)·m - q²/8.
+    let m = if q.abs() < 1e-12 { 0.0 } else { largest_cubic_root(p, p * p / 4.0 - r, -q * q / 8.0) };
+
+    let mut roots = Vec::with_capacity(4);
+    if m <= 1e-12 {
+        // no y term, so it's a quadratic in y²
+        for y_squared in solve_quadratic(p, r) {
+            if y_squared >= 0.0 {
+                roots.extend([y_squared.sqrt(), -y_squared.sqrt()]);
+            }
+        }
+    } else {
+        let s = (2.0 * m).sqrt();
+        roots.extend(solve_quadratic(-s, p / 2.0 + m + q / (2.0 * s)));
+        roots.extend(solve_quadratic(s, p / 2.0 + m - q / (2.0 * s)));
+    }
+    roots.into_iter().map(|y| y - shift).collect()
+}
+
+// Real roots of x² + b·x + c
+fn solve_quadratic(b: f64, c: f64) -> Vec<f64> {
+    let discriminant = b * b - 4.0 * c;
+    // a ray that just grazes the torus can land a hair below zero, count that as touching
+    if discriminant < -1e-9 * (b * b).max(1.0) {
+        return Vec::new();
+    }
+    // written so the two big numbers are never subtracted from each other, which would lose digits
+    let half = -0.5 * (b + b.signum() * discriminant.max(0.0).sqrt());
+    if half == 0.0 {
+        return vec![0.0];
+    }
+    vec![half, c / half]
+}
+
+// The largest real root of x³ + a·x² + b·x + c
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    let q = (a * a - 3.0 * b) / 9.0;
+    let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+    let mut x = if r * r < q * q * q {
+        // three real roots, this is the biggest one
+        let theta = (r / (q * q * q).sqrt()).clamp(-1.0, 1.0).acos();
+        -2.0 * q.sqrt() * ((theta + 2.0 * std::f64::consts::PI) / 3.0).cos() - a / 3.0
+    } else {
+        let big = -r.signum() * (r.abs() + (r * r - q * q * q).sqrt()).cbrt();
+        let small = if big == 0.0 { 0.0 } else { q / big };
+        big + small - a / 3.0
+    };
+
+    // a couple of Newton steps clean up the rounding from acos and cbrt
+    for _ in 0..2 {
+        let value = ((x + a) * x + b) * x + c;
+        let slope = (3.0 * x + 2.0 * a) * x + b;
+        if slope.abs() < 1e-15 {
+            break;
+        }
+        x -= value / slope;
+    }
+    x
+}
+
+// Newton steps on x⁴ + c3·x³ + c2·x² + c1·x + c0, only kept while they make the root better
+fn polish_root([c0, c1, c2, c3]: [f64; 4], mut x: f64) -> f64 {
+    let quartic = |x: f64| (((x + c3) * x + c2) * x + c1) * x + c0;
+    for _ in 0..4 {
+        let slope = ((4.0 * x + 3.0 * c3) * x + 2.0 * c2) * x + c1;
+        if slope.abs() < 1e-15 {
+            break;
+        }
+        let better = x - quartic(x) / slope;
+        if quartic(better).abs() >= quartic(x).abs() {
+            break;
+        }
+        x = better;
+    }
+    x
+}
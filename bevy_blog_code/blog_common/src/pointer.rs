@@ -0,0 +1,179 @@
+use bevy::{input::InputSystem, prelude::*, utils::HashMap, window::PrimaryWindow};
+
+use crate::input::{action, context, update_action_state, ActionState, EnableInputContext};
+
+// Puts the mouse and every finger on a touch screen into one list of pointers, and sends a
+// `PointerEvent` when one is pressed, moves or is let go. Systems that only care about
+// "something was tapped or clicked here" read these and work on phones as well as desktops.
+// Two fingers on the screen also make a `TwoFingerGesture` for pinch zoom, panning and twisting.
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.enable_input_context(context::PICKING)
+            .add_event::<PointerEvent>()
+            .init_resource::<Pointers>()
+            .add_systems(PreUpdate, update_pointers.after(InputSystem).after(update_action_state));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerId {
+    Mouse,
+    Touch(u64), // the id the touch screen gave the finger, it stays the same until it's lifted
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointerAction {
+    Press,
+    Move,
+    Release,
+}
+
+// Positions are in logical window pixels from the top left, like `Window::cursor_position`
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PointerEvent {
+    pub id: PointerId,
+    pub action: PointerAction,
+    pub position: Vec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Pointer {
+    pub position: Vec2,
+    pub delta: Vec2,          // how far it moved this frame
+    pub pressed: bool,        // the mouse has the pick action (left click) held, fingers always are
+    pub press_position: Vec2, // where it was last pressed
+}
+
+// Two fingers moving at the same time, for this frame only
+#[derive(Clone, Copy, Debug)]
+pub struct TwoFingerGesture {
+    pub pinch: f32,    // how much further apart the fingers are than last frame, 1.0 is no change
+    pub pan: Vec2,     // how far the point between the fingers moved, in pixels
+    pub rotation: f32, // how far the line between the fingers turned, in radians
+}
+
+// Every pointer over the window. The mouse drops out when the cursor leaves, a finger when it's lifted.
+#[derive(Resource, Default, Debug)]
+pub struct Pointers {
+    pointers: HashMap<PointerId, Pointer>,
+    gesture: Option<TwoFingerGesture>,
+}
+
+impl Pointers {
+    pub fn get(&self, id: PointerId) -> Option<&Pointer> {
+        self.pointers.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PointerId, &Pointer)> {
+        self.pointers.iter().map(|(id, pointer)| (*id, pointer))
+    }
+
+    // Fingers that are on the screen
+    pub fn touches(&self) -> impl Iterator<Item = &Pointer> {
+        self.iter().filter(|(id, _)| matches!(id, PointerId::Touch(_))).map(|(_, pointer)| pointer)
+    }
+
+    // Only while exactly two fingers are down, a third finger stops the gesture
+    pub fn gesture(&self) -> Option<TwoFingerGesture> {
+        self.gesture
+    }
+
+    fn press(&mut self, id: PointerId, position: Vec2, events: &mut EventWriter<PointerEvent>) {
+        self.move_to(id, position, events);
+        let pointer = self.pointers.get_mut(&id).unwrap();
+        pointer.pressed = true;
+        pointer.press_position = position;
+        events.send(PointerEvent { id, action: PointerAction::Press, position });
+    }
+
+    fn move_to(&mut self, id: PointerId, position: Vec2, events: &mut EventWriter<PointerEvent>) {
+        let pointer = self.pointers.entry(id).or_insert(Pointer {
+            position: Vec2::NAN, // so a new pointer always sends a move
+            delta: Vec2::ZERO,
+            pressed: false,
+            press_position: position,
+        });
+        if pointer.position == position {
+            return;
+        }
+        if !pointer.position.is_nan() {
+            pointer.delta += position - pointer.position;
+        }
+        pointer.position = position;
+        events.send(PointerEvent { id, action: PointerAction::Move, position });
+    }
+
+    fn release(&mut self, id: PointerId, events: &mut EventWriter<PointerEvent>) {
+        let Some(pointer) = self.pointers.get_mut(&id) else { return; };
+        if pointer.pressed {
+            pointer.pressed = false;
+            events.send(PointerEvent { id, action: PointerAction::Release, position: pointer.position });
+        }
+    }
+}
+
+fn update_pointers(
+    actions: Res<ActionState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<Touches>,
+    mut pointers: ResMut<Pointers>,
+    mut events: EventWriter<PointerEvent>,
+) {
+    for pointer in pointers.pointers.values_mut() {
+        pointer.delta = Vec2::ZERO;
+    }
+
+    // The mouse. A held button keeps it around after the cursor leaves the window, so the release isn't lost.
+    let cursor = windows.get_single().ok().and_then(Window::cursor_position);
+    if let Some(cursor) = cursor {
+        pointers.move_to(PointerId::Mouse, cursor, &mut events);
+        if actions.just_pressed(action::PICK) {
+            pointers.press(PointerId::Mouse, cursor, &mut events);
+        }
+    }
+    if !actions.pressed(action::PICK) {
+        pointers.release(PointerId::Mouse, &mut events);
+        if cursor.is_none() {
+            pointers.pointers.remove(&PointerId::Mouse);
+        }
+    }
+
+    // Fingers. A canceled touch (the phone took it over for a system gesture) counts as lifted.
+    for touch in touches.iter_just_pressed() {
+        pointers.press(PointerId::Touch(touch.id()), touch.position(), &mut events);
+    }
+    for touch in touches.iter() {
+        pointers.move_to(PointerId::Touch(touch.id()), touch.position(), &mut events);
+    }
+    for touch in touches.iter_just_released().chain(touches.iter_just_canceled()) {
+        let id = PointerId::Touch(touch.id());
+        pointers.move_to(id, touch.position(), &mut events);
+        pointers.release(id, &mut events);
+        pointers.pointers.remove(&id);
+    }
+
+    // sorted by touch id, so the same finger is `a` every frame and the twist keeps its sign
+    let mut fingers: Vec<(u64, &Pointer)> = pointers
+        .iter()
+        .filter_map(|(id, pointer)| match id {
+            PointerId::Touch(touch) => Some((touch, pointer)),
+            PointerId::Mouse => None,
+        })
+        .collect();
+    fingers.sort_by_key(|(touch, _)| *touch);
+    pointers.gesture = match fingers[..] {
+        [(_, a), (_, b)] => {
+            let before = (b.position - b.delta) - (a.position - a.delta);
+            let after = b.position - a.position;
+            Some(TwoFingerGesture {
+                // fingers on the same spot have no distance to compare, a pinch of 0 would zoom forever
+                pinch: if before != Vec2::ZERO && after != Vec2::ZERO { after.length() / before.length() } else { 1.0 },
+                pan: (a.delta + b.delta) / 2.0,
+                rotation: if before != Vec2::ZERO && after != Vec2::ZERO { before.angle_to(after) } else { 0.0 },
+            })
+        }
+        _ => None,
+    };
+}
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+// A tiny random number generator (xorshift), so scattering things needs no other crate and the same
+// seed always scatters them the same way. Good enough for test scenes, not for anything that has to be fair.
+#[derive(Clone, Copy, Debug)]
+pub struct Random(pub u32); // the seed, which must not be 0
+
+impl Random {
+    // A number from `min` up to, but not including, `max`
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        min + (max - min) * (self.0 >> 8) as f32 / (1 << 24) as f32
+    }
+
+    // A point in the square from -half_size to half_size on both axes
+    pub fn point(&mut self, half_size: f32) -> Vec2 {
+        Vec2::new(self.range(-half_size, half_size), self.range(-half_size, half_size))
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::{color::palettes::css::YELLOW, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    hover::HoveredEntity,
+    input::{action, context, ActionState, EnableInputContext},
+    picking::camera_under_cursor,
+};
+
+// Drag a rectangle with the left mouse button to select everything inside it, or click to select one thing.
+// Hold Shift to add to the selection and Ctrl to toggle. Works with 2D and 3D cameras.
+// Selected entities get a `Selected` marker and a yellow box around them.
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.enable_input_context(context::SELECTION)
+            .add_systems(Startup, spawn_marquee)
+            .add_systems(Update, (marquee_select, draw_selected));
+    }
+}
+
+// A box around the entity in its own space. The whole box has to be inside the rectangle to be selected.
+// For 2D shapes leave z at 0.0.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Selectable {
+    pub half_extents: Vec3,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Selected;
+
+// Set by anything that drags things itself, like the collisions example's editor, while the cursor is over
+// something it would grab. A press there drags that instead of starting a rectangle, a click still selects.
+// Whatever `PickingPlugin` hovers counts too, without setting this.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SelectionBlocked(pub bool);
+
+// A marquee smaller than this many pixels counts as a click
+const CLICK_SIZE: f32 = 4.0;
+
+// The UI node that draws the rectangle
+#[derive(Component, Default)]
+struct Marquee {
+    start: Option<Vec2>, // where the button went down, None while not selecting
+    end: Vec2,
+    over_pickable: bool, // started on something pickable or blocked, so it's dragging that instead
+}
+
+fn spawn_marquee(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Marquee"),
+        Node {
+            position_type: PositionType::Absolute,
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BorderColor(YELLOW.into()),
+        BackgroundColor(YELLOW.with_alpha(0.1).into()),
+        Visibility::Hidden,
+        Marquee::default(),
+    ));
+}
+
+fn marquee_select(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    selectables: Query<(Entity, &GlobalTransform, &Selectable, Has<Selected>)>,
+    hovered: Option<Res<HoveredEntity>>,
+    blocked: Option<Res<SelectionBlocked>>,
+    mut marquee: Query<(&mut Marquee, &mut Node, &mut Visibility)>,
+) {
+    let Ok((mut marquee, mut node, mut visibility)) = marquee.get_single_mut() else { return; };
+    let Ok(window) = windows.get_single() else { return; };
+
+    if actions.just_pressed(action::PICK) {
+        let Some(cursor) = window.cursor_position() else { return; };
+        marquee.start = Some(cursor);
+        marquee.end = cursor;
+        marquee.over_pickable = hovered.is_some_and(|hovered| hovered.0.is_some())
+            || blocked.is_some_and(|blocked| blocked.0);
+    }
+    let Some(start) = marquee.start else { return; };
+    if let Some(cursor) = window.cursor_position() {
+        marquee.end = cursor; // keeps the last position if the cursor leaves the window
+    }
+    let rect = Rect::from_corners(start, marquee.end);
+    let is_click = rect.width() < CLICK_SIZE && rect.height() < CLICK_SIZE;
+
+    if actions.pressed(action::PICK) {
+        let show = !is_click && !marquee.over_pickable;
+        *visibility = if show { Visibility::Visible } else { Visibility::Hidden };
+        node.left = Val::Px(rect.min.x);
+        node.top = Val::Px(rect.min.y);
+        node.width = Val::Px(rect.width());
+        node.height = Val::Px(rect.height());
+        return;
+    }
+
+    // the button was let go
+    *visibility = Visibility::Hidden;
+    marquee.start = None;
+    if marquee.over_pickable && !is_click {
+        return; // that was an object being dragged, not a selection
+    }
+    // with split screens, select with the camera the marquee was started in
+    let Some((camera, camera_transform, _)) = camera_under_cursor(start, &cameras) else { return; };
+
+    let on_screen = selectables.iter().filter_map(|(entity, transform, selectable, _)| {
+        let (bounds, depth) = screen_bounds(camera, camera_transform, transform, selectable.half_extents)?;
+        Some((entity, bounds, depth))
+    });
+    let hits: Vec<Entity> = if is_click {
+        // only the closest thing under the cursor
+        on_screen
+            .filter(|(_, bounds, _)| bounds.contains(start))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(entity, ..)| entity)
+            .into_iter()
+            .collect()
+    } else {
+        on_screen
+            .filter(|(_, bounds, _)| rect.contains(bounds.min) && rect.contains(bounds.max))
+            .map(|(entity, ..)| entity)
+            .collect()
+    };
+
+    let add = actions.pressed(action::SELECT_ADD);
+    let toggle = actions.pressed(action::SELECT_TOGGLE);
+    for (entity, _, _, selected) in &selectables {
+        let hit = hits.contains(&entity);
+        let select = match (hit, toggle) {
+            (true, true) => !selected,
+            (true, false) => true,
+            (false, _) => selected && (add || toggle), // a plain selection replaces the old one
+        };
+        if select && !selected {
+            commands.entity(entity).insert(Selected);
+        } else if !select && selected {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+}
+
+// The window rectangle around the entity's box and how far it is from the camera.
+// None if part of it is behind the camera.
+fn screen_bounds(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    transform: &GlobalTransform,
+    half_extents: Vec3,
+) -> Option<(Rect, f32)> {
+    let viewport_origin = camera.logical_viewport_rect()?.min;
+    let mut bounds = Rect::EMPTY;
+    for corner in 0..8 {
+        let sign = |bit: u32| if corner & bit == 0 { -1.0 } else { 1.0 };
+        let local = Vec3::new(sign(1), sign(2), sign(4)) * half_extents;
+        let screen = camera.world_to_viewport(camera_transform, transform.transform_point(local)).ok()?;
+        bounds = bounds.union_point(viewport_origin + screen);
+    }
+    let depth = camera_transform.translation().distance(transform.translation());
+    Some((bounds, depth))
+}
+
+fn draw_selected(mut gizmos: Gizmos, selected: Query<(&GlobalTransform, &Selectable), With<Selected>>) {
+    for (transform, selectable) in &selected {
+        let mut outline = transform.compute_transform();
+        outline.scale *= selectable.half_extents * 2.0;
+        gizmos.cuboid(outline, YELLOW);
+    }
+}
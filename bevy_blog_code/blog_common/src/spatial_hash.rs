@@ -0,0 +1,139 @@
+use bevy::{prelude::*, utils::HashMap};
+
+// A grid of square cells laid over the plane. Each entity is listed in every cell its bounding box touches,
+// so finding what's near a spot only looks at a few cells instead of at every entity.
+// `CollisionPlugin` keeps one up to date for every `Collider`. To change the cell size, insert your own
+// before adding the plugin. Cells about the size of the common obstacles work best.
+#[derive(Resource, Debug)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    entries: HashMap<Entity, Entry>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    bounds: Rect,
+    cells: IRect, // the first and last cell it's in, both included
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        SpatialHash::new(100.0)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialHash { cell_size, cells: HashMap::default(), entries: HashMap::default() }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn bounds(&self, entity: Entity) -> Option<Rect> {
+        self.entries.get(&entity).map(|entry| entry.bounds)
+    }
+
+    // Adds the entity, or moves it if it's already in. Only the cells it left or entered are touched,
+    // and moving around inside the same cells costs next to nothing.
+    pub fn insert(&mut self, entity: Entity, bounds: Rect) {
+        let cells = self.cell_range(bounds);
+        let old = self.entries.insert(entity, Entry { bounds, cells }).map(|entry| entry.cells);
+        if old == Some(cells) {
+            return;
+        }
+        if let Some(old) = old {
+            for cell in cells_in(old).filter(|cell| !contains(cells, *cell)) {
+                self.remove_from_cell(cell, entity);
+            }
+        }
+        for cell in cells_in(cells).filter(|cell| old.is_none_or(|old| !contains(old, *cell))) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some(entry) = self.entries.remove(&entity) else { return; };
+        for cell in cells_in(entry.cells) {
+            self.remove_from_cell(cell, entity);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    // Every entity whose bounding box overlaps the region, each one once
+    pub fn query(&self, region: Rect) -> Vec<Entity> {
+        let range = self.cell_range(region);
+        let mut found = Vec::new();
+        let mut check_cell = |cell: IVec2, entities: &Vec<Entity>| {
+            for entity in entities {
+                let entry = &self.entries[entity];
+                // something in several cells is only taken from the first cell it shares with the region
+                let first = entry.cells.min.max(range.min);
+                if cell == first && overlaps(entry.bounds, region) {
+                    found.push(*entity);
+                }
+            }
+        };
+
+        // a huge region, like a zoomed out camera, has fewer filled cells than cells to look at
+        let span = |min: i32, max: i32| max as i64 - min as i64 + 1;
+        let cell_count = span(range.min.x, range.max.x).saturating_mul(span(range.min.y, range.max.y));
+        if cell_count > self.cells.len() as i64 {
+            for (cell, entities) in &self.cells {
+                if contains(range, *cell) {
+                    check_cell(*cell, entities);
+                }
+            }
+        } else {
+            for cell in cells_in(range) {
+                if let Some(entities) = self.cells.get(&cell) {
+                    check_cell(cell, entities);
+                }
+            }
+        }
+        found
+    }
+
+    fn cell_range(&self, bounds: Rect) -> IRect {
+        // `as` stops at the biggest i32, so even an endless rectangle gives a range
+        let cell = |point: Vec2| (point / self.cell_size).floor().as_ivec2();
+        IRect::from_corners(cell(bounds.min), cell(bounds.max))
+    }
+
+    fn remove_from_cell(&mut self, cell: IVec2, entity: Entity) {
+        let Some(entities) = self.cells.get_mut(&cell) else { return; };
+        if let Some(index) = entities.iter().position(|other| *other == entity) {
+            entities.swap_remove(index);
+        }
+        if entities.is_empty() {
+            self.cells.remove(&cell);
+        }
+    }
+}
+
+fn cells_in(range: IRect) -> impl Iterator<Item = IVec2> {
+    (range.min.y..=range.max.y).flat_map(move |y| (range.min.x..=range.max.x).map(move |x| IVec2::new(x, y)))
+}
+
+fn contains(range: IRect, cell: IVec2) -> bool {
+    cell.cmpge(range.min).all() && cell.cmple(range.max).all()
+}
+
+// Touching edges count, so a point or a flat line still finds things
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.min.cmple(b.max).all() && b.min.cmple(a.max).all()
+}
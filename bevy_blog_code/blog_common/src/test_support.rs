@@ -0,0 +1,21 @@
+use std::time::{Duration, Instant};
+
+use bevy::{asset::LoadState, prelude::*};
+
+// Helpers for the tests of blog_common and the examples
+
+// Loads a file through the app's asset server, the way the examples do, and updates the app until
+// it has loaded or failed. Gives up after 10 seconds and returns whatever state it was in then.
+pub fn load_asset<A: Asset>(app: &mut App, path: &str) -> (Handle<A>, LoadState) {
+    let handle = app.world().resource::<AssetServer>().load(path.to_string());
+    // loading happens on other threads, give it a moment
+    let started = Instant::now();
+    loop {
+        app.update();
+        let state = app.world().resource::<AssetServer>().load_state(&handle);
+        if !matches!(state, LoadState::Loading | LoadState::NotLoaded) || started.elapsed() > Duration::from_secs(10) {
+            return (handle, state);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,344 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, RenderAssetUsages},
+    color::palettes::css::{DIM_GRAY, ORANGE_RED},
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+use crate::collision::{collide, move_and_slide, Collider, Slide};
+
+// Draws every `Tilemap` as a handful of big meshes, one for each block of `CHUNK_SIZE` by `CHUNK_SIZE` tiles.
+// That's a few draw calls for the whole map, and blocks off screen are skipped by the renderer.
+// Changing the map builds the meshes again.
+// Map files (`.map.txt` and `.map.csv`) load as `Tilemap` assets, and are loaded again when they're saved.
+pub struct TilemapPlugin;
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Tilemap>()
+            .init_asset_loader::<TilemapLoader>()
+            .add_systems(Update, build_tile_meshes);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(crate::asset_watch::AssetWatchPlugin::<Tilemap>::default());
+    }
+}
+
+// How many tiles across each mesh is
+pub const CHUNK_SIZE: u32 = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tile {
+    #[default]
+    Empty,
+    Solid,  // a wall
+    Hazard, // can be walked into, what happens then is up to the game
+}
+
+// A grid of square tiles, read from a text file with one character per tile:
+//
+//     #  solid      .  empty      ^  hazard      @  empty, and where the player starts
+//
+// or from a CSV file with 0 for empty, 1 for solid and 2 for hazard (the characters work there too).
+// The first line is the top row. Everything outside the map counts as solid, so nothing can leave it.
+// The map is placed with `origin`, leave its transform at the default.
+#[derive(Component, Asset, TypePath, Clone, Debug)]
+#[require(Transform, Visibility)]
+pub struct Tilemap {
+    pub origin: Vec2, // where the bottom left corner of the map is in the world
+    pub tile_size: f32,
+    pub spawn: Option<UVec2>, // the tile marked with `@`
+    pub solid_color: Color,
+    pub hazard_color: Color,
+    width: u32,
+    height: u32,
+    tiles: Vec<Tile>, // row by row, starting at the bottom
+}
+
+// What's wrong with a map file, and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct TilemapError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for TilemapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for TilemapError {}
+
+impl Tilemap {
+    // An empty map, to fill in with `set`
+    pub fn new(width: u32, height: u32, tile_size: f32) -> Self {
+        Tilemap {
+            origin: Vec2::ZERO,
+            tile_size,
+            spawn: None,
+            solid_color: DIM_GRAY.into(),
+            hazard_color: ORANGE_RED.into(),
+            width,
+            height,
+            tiles: vec![Tile::Empty; (width * height) as usize],
+        }
+    }
+
+    pub fn parse(text: &str, tile_size: f32) -> Result<Self, TilemapError> {
+        let error = |line: usize, column: usize, message: String| TilemapError { line, column, message };
+        let mut rows: Vec<(usize, Vec<Tile>)> = Vec::new();
+        let mut spawn = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let cells: Vec<&str> = if line.contains(',') {
+                line.split(',').map(str::trim).collect()
+            } else {
+                line.char_indices().map(|(start, c)| &line[start..start + c.len_utf8()]).collect()
+            };
+
+            let mut row = Vec::with_capacity(cells.len());
+            for (column, cell) in cells.iter().enumerate() {
+                let tile = match *cell {
+                    "." | "0" | "" => Tile::Empty,
+                    "#" | "1" => Tile::Solid,
+                    "^" | "2" => Tile::Hazard,
+                    "@" => {
+                        if spawn.is_some() {
+                            return Err(error(line_number, column + 1, "there's more than one @".to_string()));
+                        }
+                        spawn = Some((rows.len(), column));
+                        Tile::Empty
+                    }
+                    other => {
+                        let message = format!("`{other}` isn't a tile, use # . ^ @ or 0 1 2");
+                        return Err(error(line_number, column + 1, message));
+                    }
+                };
+                row.push(tile);
+            }
+
+            if let Some((first_line, first)) = rows.first() {
+                if row.len() != first.len() {
+                    let message = format!(
+                        "this row is {} tiles wide, the first row (line {first_line}) is {}",
+                        row.len(),
+                        first.len()
+                    );
+                    return Err(error(line_number, row.len().min(first.len()) + 1, message));
+                }
+            }
+            rows.push((line_number, row));
+        }
+
+        let Some((_, first)) = rows.first() else {
+            return Err(error(1, 1, "the map has no tiles".to_string()));
+        };
+        let (width, height) = (first.len() as u32, rows.len() as u32);
+        let mut map = Tilemap::new(width, height, tile_size);
+        // the file lists the top row first
+        for (row_index, (_, row)) in rows.iter().enumerate() {
+            let y = height - 1 - row_index as u32;
+            for (x, tile) in row.iter().enumerate() {
+                map.set(UVec2::new(x as u32, y), *tile);
+            }
+        }
+        map.spawn = spawn.map(|(row, column)| UVec2::new(column as u32, height - 1 - row as u32));
+        Ok(map)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // None outside the map
+    pub fn get(&self, tile: IVec2) -> Option<Tile> {
+        let inside = tile.cmpge(IVec2::ZERO).all() && tile.x < self.width as i32 && tile.y < self.height as i32;
+        inside.then(|| self.tiles[(tile.y as u32 * self.width + tile.x as u32) as usize])
+    }
+
+    pub fn set(&mut self, tile: UVec2, value: Tile) {
+        if tile.x < self.width && tile.y < self.height {
+            self.tiles[(tile.y * self.width + tile.x) as usize] = value;
+        }
+    }
+
+    // The part of the world the map covers
+    pub fn bounds(&self) -> Rect {
+        let size = Vec2::new(self.width as f32, self.height as f32) * self.tile_size;
+        Rect::from_corners(self.origin, self.origin + size)
+    }
+
+    // The tile a point is on, which can be outside the map
+    pub fn tile_at(&self, point: Vec2) -> IVec2 {
+        ((point - self.origin) / self.tile_size).floor().as_ivec2()
+    }
+
+    pub fn tile_center(&self, tile: IVec2) -> Vec2 {
+        self.origin + (tile.as_vec2() + 0.5) * self.tile_size
+    }
+
+    // Every tile that touches the region, with None for the ones outside the map
+    pub fn tiles_in(&self, region: Rect) -> impl Iterator<Item = (IVec2, Option<Tile>)> + '_ {
+        let (min, max) = (self.tile_at(region.min), self.tile_at(region.max));
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| (IVec2::new(x, y), self.get(IVec2::new(x, y)))))
+    }
+
+    // Moves a shape like `collision::move_and_slide`, against the solid tiles near its path.
+    // Only the few tiles around the move are looked at, however big the map is.
+    pub fn move_and_slide(&self, collider: &Collider, at: Isometry2d, motion: Vec2) -> TileSlide {
+        let end = Isometry2d::new(at.translation + motion, at.rotation);
+        let reach = collider.bounds(at).union(collider.bounds(end));
+        // each wall is named by its tile, so the hits say which tiles were run into
+        let block = Collider::Aabb { half_extents: Vec2::splat(self.tile_size / 2.0) };
+        let walls: Vec<_> = self
+            .tiles_in(reach)
+            .filter(|(_, tile)| tile.is_none_or(|tile| tile == Tile::Solid))
+            .map(|(tile, _)| (tile, &block, Isometry2d::from_translation(self.tile_center(tile))))
+            .collect();
+        move_and_slide(collider, at, motion, &walls)
+    }
+
+    // Whether the shape overlaps any tile of this kind, like a hazard
+    pub fn touches(&self, kind: Tile, collider: &Collider, at: Isometry2d) -> bool {
+        let block = Collider::Aabb { half_extents: Vec2::splat(self.tile_size / 2.0) };
+        self.tiles_in(collider.bounds(at)).any(|(tile, found)| {
+            found == Some(kind)
+                && collide(collider, at, &block, Isometry2d::from_translation(self.tile_center(tile))).is_some()
+        })
+    }
+
+    // One mesh with a square for every tile in the chunk that isn't empty, or None if they all are
+    fn chunk_mesh(&self, chunk: UVec2) -> Option<Mesh> {
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        let mut colors: Vec<[f32; 4]> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        let start = chunk * CHUNK_SIZE;
+        let end = (start + CHUNK_SIZE).min(UVec2::new(self.width, self.height));
+        for y in start.y..end.y {
+            for x in start.x..end.x {
+                let color = match self.get(IVec2::new(x as i32, y as i32)) {
+                    Some(Tile::Solid) => self.solid_color,
+                    Some(Tile::Hazard) => self.hazard_color,
+                    _ => continue,
+                };
+                let first = positions.len() as u32;
+                let corner = Vec2::new(x as f32, y as f32) * self.tile_size;
+                for offset in [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y] {
+                    positions.push((corner + offset * self.tile_size).extend(0.0).to_array());
+                    colors.push(color.to_linear().to_f32_array());
+                }
+                indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+            }
+        }
+        if positions.is_empty() {
+            return None;
+        }
+
+        let mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+            .with_inserted_indices(Indices::U32(indices));
+        Some(mesh)
+    }
+}
+
+// Everything that can go wrong reading a map file. Bevy logs it along with the file's name.
+#[derive(Debug)]
+pub enum TilemapLoadError {
+    Io(std::io::Error),
+    Text(std::str::Utf8Error),
+    Map(TilemapError),
+}
+
+impl std::fmt::Display for TilemapLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TilemapLoadError::Io(err) => write!(f, "could not read the file: {err}"),
+            TilemapLoadError::Text(err) => write!(f, "the file isn't text: {err}"),
+            TilemapLoadError::Map(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for TilemapLoadError {}
+
+impl From<std::io::Error> for TilemapLoadError {
+    fn from(err: std::io::Error) -> Self {
+        TilemapLoadError::Io(err)
+    }
+}
+
+// The file only says what the tiles are, so loaded maps get tiles `LOADED_TILE_SIZE` wide.
+// Set `tile_size` on the copy that goes in the world. It isn't a loader setting because
+// `AssetServer::reload` loads again with the default settings.
+pub const LOADED_TILE_SIZE: f32 = 50.0;
+
+#[derive(Default)]
+pub struct TilemapLoader;
+
+impl AssetLoader for TilemapLoader {
+    type Asset = Tilemap;
+    type Settings = ();
+    type Error = TilemapLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _context: &mut LoadContext<'_>,
+    ) -> Result<Tilemap, TilemapLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = std::str::from_utf8(&bytes).map_err(TilemapLoadError::Text)?;
+        Tilemap::parse(text, LOADED_TILE_SIZE).map_err(TilemapLoadError::Map)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["map.txt", "map.csv"]
+    }
+}
+
+// Where a shape ended up after moving through a tilemap, and the wall tiles it ran into on the way
+pub type TileSlide = Slide<IVec2>;
+
+fn build_tile_meshes(
+    mut commands: Commands,
+    maps: Query<(Entity, &Tilemap), Changed<Tilemap>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut material: Local<Option<Handle<ColorMaterial>>>,
+) {
+    for (entity, map) in &maps {
+        // white, so the tiles keep their own colours
+        let material = material.get_or_insert_with(|| materials.add(ColorMaterial::default())).clone();
+        let chunks = UVec2::new(map.width.div_ceil(CHUNK_SIZE), map.height.div_ceil(CHUNK_SIZE));
+
+        let mut entity = commands.entity(entity);
+        entity.despawn_descendants();
+        entity.with_children(|parent| {
+            for y in 0..chunks.y {
+                for x in 0..chunks.x {
+                    let Some(mesh) = map.chunk_mesh(UVec2::new(x, y)) else { continue; };
+                    parent.spawn((
+                        Mesh2d(meshes.add(mesh)),
+                        MeshMaterial2d(material.clone()),
+                        // behind the player and anything else drawn at 0
+                        Transform::from_translation(map.origin.extend(-1.0)),
+                    ));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,277 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, SQRT_2, TAU};
+
+use bevy::prelude::*;
+use blog_common::collision::{collide, move_and_slide, Collider, Contact};
+use proptest::prelude::*;
+
+const CIRCLE: Collider = Collider::Circle { radius: 1.0 };
+const AABB: Collider = Collider::Aabb { half_extents: Vec2::ONE };
+const OBB: Collider = Collider::Obb { half_extents: Vec2::ONE };
+const CAPSULE: Collider = Collider::Capsule { half_length: 1.0, radius: 0.5 };
+
+// Pointing up, with its flat side at the bottom
+fn triangle() -> Collider {
+    Collider::Polygon { points: vec![Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(0.0, 1.0)] }
+}
+
+fn square(half_size: f32) -> Collider {
+    let h = half_size;
+    Collider::Polygon { points: vec![Vec2::new(-h, -h), Vec2::new(h, -h), Vec2::new(h, h), Vec2::new(-h, h)] }
+}
+
+fn at(x: f32, y: f32) -> Isometry2d {
+    Isometry2d::from_xy(x, y)
+}
+
+fn turned(x: f32, y: f32, angle: f32) -> Isometry2d {
+    Isometry2d::new(Vec2::new(x, y), Rot2::radians(angle))
+}
+
+fn assert_contact(found: Option<Contact>, normal: Vec2, depth: f32, point: Vec2) {
+    let found = found.expect("expected the shapes to overlap");
+    assert!(found.normal.abs_diff_eq(normal, 1e-4), "normal {} instead of {normal}", found.normal);
+    assert!((found.depth - depth).abs() < 1e-4, "depth {} instead of {depth}", found.depth);
+    assert!(found.point.abs_diff_eq(point, 1e-4), "point {} instead of {point}", found.point);
+}
+
+// How far the corner of a 45 degree turned unit box reaches
+const CORNER: f32 = SQRT_2;
+
+#[test]
+fn circle_circle() {
+    assert_contact(collide(&CIRCLE, at(0.0, 0.0), &CIRCLE, at(1.5, 0.0)), Vec2::X, 0.5, Vec2::new(0.75, 0.0));
+}
+
+#[test]
+fn circle_aabb() {
+    assert_contact(collide(&CIRCLE, at(0.0, 0.0), &AABB, at(1.5, 0.5)), Vec2::X, 0.5, Vec2::new(0.75, 0.0));
+}
+
+#[test]
+fn circle_obb() {
+    // the box's corner pokes into the circle
+    let depth = 1.0 - (2.0 - CORNER);
+    let point = Vec2::new((1.0 + 2.0 - CORNER) / 2.0, 0.0);
+    assert_contact(collide(&CIRCLE, at(0.0, 0.0), &OBB, turned(2.0, 0.0, FRAC_PI_4)), Vec2::X, depth, point);
+}
+
+#[test]
+fn circle_capsule() {
+    assert_contact(collide(&CIRCLE, at(0.0, 0.0), &CAPSULE, at(1.2, 0.5)), Vec2::X, 0.3, Vec2::new(0.85, 0.0));
+}
+
+#[test]
+fn circle_polygon() {
+    assert_contact(collide(&CIRCLE, at(0.0, 0.0), &triangle(), at(0.0, 1.5)), Vec2::Y, 0.5, Vec2::new(0.0, 0.75));
+}
+
+#[test]
+fn aabb_aabb() {
+    // the point is in the middle of where the two faces line up
+    assert_contact(collide(&AABB, at(0.0, 0.0), &AABB, at(1.5, 0.5)), Vec2::X, 0.5, Vec2::new(0.75, 0.25));
+}
+
+#[test]
+fn aabb_ignores_rotation() {
+    let found = collide(&AABB, turned(0.0, 0.0, 0.3), &AABB, turned(1.5, 0.5, 1.0));
+    assert_contact(found, Vec2::X, 0.5, Vec2::new(0.75, 0.25));
+}
+
+#[test]
+fn aabb_obb() {
+    let depth = 1.0 - (2.0 - CORNER);
+    let point = Vec2::new((1.0 + 2.0 - CORNER) / 2.0, 0.0);
+    assert_contact(collide(&AABB, at(0.0, 0.0), &OBB, turned(2.0, 0.0, FRAC_PI_4)), Vec2::X, depth, point);
+}
+
+#[test]
+fn aabb_capsule() {
+    assert_contact(collide(&AABB, at(0.0, 0.0), &CAPSULE, at(1.3, 0.0)), Vec2::X, 0.2, Vec2::new(0.9, 0.0));
+}
+
+#[test]
+fn aabb_polygon() {
+    assert_contact(collide(&AABB, at(0.0, 0.0), &triangle(), at(0.0, 1.5)), Vec2::Y, 0.5, Vec2::new(0.0, 0.75));
+}
+
+#[test]
+fn obb_obb() {
+    // side by side along the first box's own X axis
+    let angle = PI / 6.0;
+    let rotation = Rot2::radians(angle);
+    let second = rotation * Vec2::new(1.5, 0.0);
+    let found = collide(&OBB, turned(0.0, 0.0, angle), &OBB, turned(second.x, second.y, angle));
+    assert_contact(found, rotation * Vec2::X, 0.5, rotation * Vec2::new(0.75, 0.0));
+}
+
+#[test]
+fn obb_capsule() {
+    let depth = 0.5 - (1.8 - CORNER);
+    let point = Vec2::new((CORNER + 1.3) / 2.0, 0.0);
+    assert_contact(collide(&OBB, turned(0.0, 0.0, FRAC_PI_4), &CAPSULE, at(1.8, 0.0)), Vec2::X, depth, point);
+}
+
+#[test]
+fn obb_polygon() {
+    let depth = CORNER - 1.0;
+    let point = Vec2::new(0.0, (CORNER + 1.0) / 2.0);
+    assert_contact(collide(&OBB, turned(0.0, 0.0, FRAC_PI_4), &triangle(), at(0.0, 2.0)), Vec2::Y, depth, point);
+}
+
+#[test]
+fn capsule_capsule() {
+    // a standing capsule touching the middle of a lying one
+    let found = collide(&CAPSULE, at(0.0, 0.0), &CAPSULE, turned(0.0, 1.8, FRAC_PI_2));
+    assert_contact(found, Vec2::Y, 0.2, Vec2::new(0.0, 1.4));
+}
+
+#[test]
+fn capsule_polygon() {
+    assert_contact(collide(&CAPSULE, at(0.0, 0.0), &triangle(), at(0.0, 2.2)), Vec2::Y, 0.3, Vec2::new(0.0, 1.35));
+}
+
+#[test]
+fn polygon_polygon() {
+    // the tip of the triangle pokes into the bottom of the square
+    let found = collide(&triangle(), at(0.0, 0.0), &square(0.5), at(0.2, 1.4));
+    assert_contact(found, Vec2::Y, 0.1, Vec2::new(0.0, 0.95));
+}
+
+#[test]
+fn apart_shapes_do_not_collide() {
+    let shapes = [CIRCLE, AABB, OBB, CAPSULE, triangle()];
+    for a in &shapes {
+        for b in &shapes {
+            assert_eq!(collide(a, at(0.0, 0.0), b, turned(3.5, 0.5, 0.4)), None, "{a:?} and {b:?}");
+        }
+    }
+}
+
+#[test]
+fn touching_shapes_do_not_collide() {
+    assert_eq!(collide(&AABB, at(0.0, 0.0), &AABB, at(2.0, 0.0)), None);
+    assert_eq!(collide(&CIRCLE, at(0.0, 0.0), &CIRCLE, at(0.0, 2.0)), None);
+}
+
+#[test]
+fn circle_misses_the_box_corner() {
+    // their bounding boxes overlap, but the circle passes the rounded-off corner
+    let circle = Collider::Circle { radius: 0.8 };
+    assert_eq!(collide(&circle, at(1.6, 1.6), &AABB, at(0.0, 0.0)), None);
+}
+
+#[test]
+fn capsules_end_to_end_do_not_collide() {
+    // on the same line, only the direction along the capsules tells them apart
+    assert_eq!(collide(&CAPSULE, at(0.0, 0.0), &CAPSULE, at(0.0, 3.2)), None);
+}
+
+#[test]
+fn circles_on_top_of_each_other_still_push_apart() {
+    let found = collide(&CIRCLE, at(1.0, 1.0), &CIRCLE, at(1.0, 1.0)).unwrap();
+    assert!((found.depth - 2.0).abs() < 1e-6);
+    assert!(found.normal.is_normalized());
+}
+
+#[test]
+fn a_box_slides_along_a_wall() {
+    // boxes aren't swept, so this goes through the search for where it first touches
+    let player = Collider::Aabb { half_extents: Vec2::splat(10.0) };
+    let wall = Collider::Aabb { half_extents: Vec2::new(10.0, 1000.0) };
+    let obstacles = [(Entity::from_raw(1), &wall, at(100.0, 0.0))];
+    let slide = move_and_slide(&player, at(0.0, 0.0), Vec2::new(100.0, 60.0), &obstacles);
+    assert!(slide.position.x <= 80.0 && slide.position.x > 79.9, "ended at {}", slide.position);
+    assert!((slide.position.y - 60.0).abs() < 0.1, "ended at {}", slide.position);
+    assert_eq!(slide.hits.len(), 1);
+    assert!(slide.hits[0].1.normal.abs_diff_eq(Vec2::X, 1e-4), "normal {}", slide.hits[0].1.normal);
+}
+
+#[test]
+fn sliding_into_a_corner_stops_there() {
+    let player = Collider::Circle { radius: 20.0 };
+    let wall = Collider::Aabb { half_extents: Vec2::new(10.0, 200.0) };
+    let floor = Collider::Aabb { half_extents: Vec2::new(200.0, 10.0) };
+    let obstacles = [(Entity::from_raw(1), &wall, at(100.0, 0.0)), (Entity::from_raw(2), &floor, at(0.0, -100.0))];
+    // heading down and right, mostly down, so it lands on the floor first and slides into the wall
+    let slide = move_and_slide(&player, at(0.0, 0.0), Vec2::new(300.0, -500.0), &obstacles);
+    assert!(slide.position.abs_diff_eq(Vec2::new(70.0, -70.0), 0.1), "ended at {}", slide.position);
+    let mut hit: Vec<Entity> = slide.hits.iter().map(|(entity, _)| *entity).collect();
+    hit.sort();
+    assert_eq!(hit, [Entity::from_raw(1), Entity::from_raw(2)]);
+
+    // pushing on into the corner goes nowhere
+    let again = move_and_slide(&player, at(slide.position.x, slide.position.y), Vec2::new(10.0, -10.0), &obstacles);
+    assert!(again.position.abs_diff_eq(slide.position, 0.1), "moved to {}", again.position);
+}
+
+#[test]
+fn starting_inside_an_obstacle_pushes_out() {
+    let player = Collider::Circle { radius: 20.0 };
+    // the player's middle is inside the box, and the closest way out is its left side at x = -20
+    let block = Collider::Aabb { half_extents: Vec2::splat(30.0) };
+    let obstacles = [(Entity::from_raw(1), &block, at(10.0, 0.0))];
+    let slide = move_and_slide(&player, at(0.0, 0.0), Vec2::ZERO, &obstacles);
+    assert!(slide.position.abs_diff_eq(Vec2::new(-40.0, 0.0), 1e-3), "ended at {}", slide.position);
+    assert_eq!(slide.hits.len(), 1);
+    assert!(collide(&player, at(slide.position.x, slide.position.y), &block, at(10.0, 0.0))
+        .is_none_or(|left| left.depth < 1e-3));
+
+    // and a move back into it after getting out still stops at its side
+    let slide = move_and_slide(&player, at(0.0, 0.0), Vec2::new(5.0, 0.0), &obstacles);
+    assert!(slide.position.x <= -40.0 + 1e-3 && slide.position.x > -40.1, "ended at {}", slide.position);
+}
+
+fn any_collider() -> impl Strategy<Value = Collider> {
+    prop_oneof![
+        (0.1f32..2.0).prop_map(|radius| Collider::Circle { radius }),
+        (0.1f32..2.0, 0.1f32..2.0).prop_map(|(x, y)| Collider::Aabb { half_extents: Vec2::new(x, y) }),
+        (0.1f32..2.0, 0.1f32..2.0).prop_map(|(x, y)| Collider::Obb { half_extents: Vec2::new(x, y) }),
+        (0.0f32..2.0, 0.1f32..1.0).prop_map(|(half_length, radius)| Collider::Capsule { half_length, radius }),
+        // points around a circle in order are always convex
+        (prop::collection::vec(0.0f32..0.8, 3..8), 0.5f32..2.0).prop_map(|(jitters, radius)| {
+            let count = jitters.len() as f32;
+            let points = jitters
+                .iter()
+                .enumerate()
+                .map(|(i, jitter)| Vec2::from_angle((i as f32 + jitter) / count * TAU) * radius)
+                .collect();
+            Collider::Polygon { points }
+        }),
+    ]
+}
+
+fn any_pose() -> impl Strategy<Value = Isometry2d> {
+    (-3.0f32..3.0, -3.0f32..3.0, -PI..PI).prop_map(|(x, y, angle)| turned(x, y, angle))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2000))]
+
+    // Swapping the shapes gives the same contact with the normal turned around
+    #[test]
+    fn the_order_does_not_matter(a in any_collider(), a_at in any_pose(), b in any_collider(), b_at in any_pose()) {
+        let forward = collide(&a, a_at, &b, b_at);
+        let backward = collide(&b, b_at, &a, a_at);
+        prop_assert_eq!(forward.is_some(), backward.is_some());
+        if let (Some(forward), Some(backward)) = (forward, backward.map(Contact::flipped)) {
+            prop_assert!((forward.depth - backward.depth).abs() < 1e-3, "{:?} {:?}", forward, backward);
+            // equally good ways out can be picked in either order, the depth has to match but not the normal
+            if forward.normal.abs_diff_eq(backward.normal, 1e-3) {
+                prop_assert!(forward.point.abs_diff_eq(backward.point, 1e-3), "{:?} {:?}", forward, backward);
+            }
+        }
+    }
+
+    // Moving the first shape back along the normal by the depth pulls them apart
+    #[test]
+    fn moving_by_the_depth_separates(a in any_collider(), a_at in any_pose(), b in any_collider(), b_at in any_pose()) {
+        let Some(contact) = collide(&a, a_at, &b, b_at) else { return Ok(()); };
+        prop_assert!(contact.normal.is_normalized());
+        prop_assert!(contact.depth > 0.0);
+
+        let moved = Isometry2d::new(a_at.translation - contact.normal * contact.depth, a_at.rotation);
+        if let Some(left) = collide(&a, moved, &b, b_at) {
+            prop_assert!(left.depth < 1e-3, "still {} deep after moving by {:?}", left.depth, contact);
+        }
+    }
+}
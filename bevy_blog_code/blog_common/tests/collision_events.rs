@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use blog_common::collision::{Collider, CollisionEnded, CollisionPlugin, CollisionStarted, Collisions, Contact};
+
+// A headless app that only moves transforms and looks for collisions
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, CollisionPlugin));
+    app
+}
+
+fn circle(app: &mut App, x: f32) -> Entity {
+    app.world_mut().spawn((Transform::from_xyz(x, 0.0, 0.0), Collider::Circle { radius: 10.0 })).id()
+}
+
+fn move_to(app: &mut App, entity: Entity, x: f32) {
+    app.world_mut().get_mut::<Transform>(entity).unwrap().translation.x = x;
+}
+
+// The pairs that started and ended touching since the last call
+fn events(app: &mut App) -> (Vec<(Entity, Entity)>, Vec<(Entity, Entity)>) {
+    let world = app.world_mut();
+    let started = world.resource_mut::<Events<CollisionStarted>>().drain().map(|event| (event.a, event.b)).collect();
+    let ended = world.resource_mut::<Events<CollisionEnded>>().drain().map(|event| (event.a, event.b)).collect();
+    (started, ended)
+}
+
+fn touching(app: &App) -> Vec<(Entity, Entity)> {
+    app.world().resource::<Collisions>().iter().map(|(a, b, _)| (a, b)).collect()
+}
+
+#[test]
+fn overlapping_starts_stays_and_ends() {
+    let mut app = app();
+    let a = circle(&mut app, 0.0);
+    let b = circle(&mut app, 15.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![(a, b)], vec![]));
+    let contact = app.world().resource::<Collisions>().contact(a, b).unwrap();
+    assert_eq!(contact.normal, Vec2::X);
+    assert_eq!(app.world().resource::<Collisions>().contact(b, a).unwrap().normal, Vec2::NEG_X);
+
+    // moving while still overlapping doesn't start it again
+    move_to(&mut app, b, 12.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![], vec![]));
+    assert_eq!(touching(&app), [(a, b)]);
+
+    move_to(&mut app, b, 50.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![], vec![(a, b)]));
+    assert!(touching(&app).is_empty());
+}
+
+#[test]
+fn pairs_where_nothing_moved_carry_over() {
+    let mut app = app();
+    let a = circle(&mut app, 0.0);
+    let b = circle(&mut app, 15.0);
+    let far = circle(&mut app, 100.0);
+    app.update();
+    events(&mut app);
+
+    for _ in 0..3 {
+        app.update();
+        assert_eq!(events(&mut app), (vec![], vec![]));
+        assert_eq!(touching(&app), [(a, b)]);
+    }
+
+    // something else moving doesn't disturb them
+    move_to(&mut app, far, 200.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![], vec![]));
+    assert_eq!(touching(&app), [(a, b)]);
+}
+
+#[test]
+fn reported_pairs_count_as_touching() {
+    let mut app = app();
+    let a = circle(&mut app, 0.0);
+    let b = circle(&mut app, 30.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![], vec![]));
+
+    // movement code stopped `b` against `a`, reported from b's side
+    let contact = Contact { normal: Vec2::NEG_X, depth: 0.0, point: Vec2::new(20.0, 0.0) };
+    app.world_mut().resource_mut::<Collisions>().report(b, a, contact);
+    app.update();
+    assert_eq!(events(&mut app), (vec![(a, b)], vec![]));
+    assert_eq!(app.world().resource::<Collisions>().contact(b, a), Some(contact));
+    assert_eq!(app.world().resource::<Collisions>().contact(a, b), Some(contact.flipped()));
+
+    // standing still against it keeps touching without a new report
+    app.update();
+    assert_eq!(touching(&app), [(a, b)]);
+
+    move_to(&mut app, b, 40.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![], vec![(a, b)]));
+}
+
+#[test]
+fn a_pair_both_reported_and_overlapping_starts_once() {
+    let mut app = app();
+    let a = circle(&mut app, 0.0);
+    let b = circle(&mut app, 15.0);
+    let contact = Contact { normal: Vec2::X, depth: 5.0, point: Vec2::new(7.5, 0.0) };
+    app.world_mut().resource_mut::<Collisions>().report(a, b, contact);
+    app.update();
+    assert_eq!(events(&mut app), (vec![(a, b)], vec![]));
+    assert_eq!(touching(&app), [(a, b)]);
+}
+
+#[test]
+fn despawning_ends_the_collision() {
+    let mut app = app();
+    let a = circle(&mut app, 0.0);
+    let b = circle(&mut app, 15.0);
+    app.update();
+    events(&mut app);
+
+    app.world_mut().despawn(b);
+    app.update();
+    assert_eq!(events(&mut app), (vec![], vec![(a, b)]));
+    assert!(touching(&app).is_empty());
+
+    // and the despawned entity can't be found near `a` any more
+    let c = circle(&mut app, -15.0);
+    app.update();
+    assert_eq!(events(&mut app), (vec![(a, c)], vec![]));
+}
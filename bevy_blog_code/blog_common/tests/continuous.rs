@@ -0,0 +1,176 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use bevy::prelude::*;
+use blog_common::collision::{collide, move_and_slide, time_of_impact, Collider};
+use proptest::prelude::*;
+
+// The size of the player in collisions_example_after
+const RADIUS: f32 = 20.0;
+
+fn at(x: f32, y: f32) -> Isometry2d {
+    Isometry2d::from_xy(x, y)
+}
+
+// What the old `check_collisions` did: only look at where the move ends
+fn discrete_hit(start: Vec2, motion: Vec2, obstacle: &Collider, obstacle_at: Isometry2d) -> bool {
+    let player = Collider::Circle { radius: RADIUS };
+    collide(&player, Isometry2d::from_translation(start + motion), obstacle, obstacle_at).is_some()
+}
+
+fn assert_impact(start: Vec2, motion: Vec2, obstacle: &Collider, obstacle_at: Isometry2d, time: f32, normal: Vec2) {
+    assert!(!discrete_hit(start, motion, obstacle, obstacle_at), "the end of the move should be past the obstacle");
+    let (found, contact) = time_of_impact(RADIUS, start, motion, obstacle, obstacle_at).expect("should hit");
+    assert!((found - time).abs() < 1e-4, "hit at {found} instead of {time}");
+    assert!(contact.normal.abs_diff_eq(normal, 1e-4), "normal {} instead of {normal}", contact.normal);
+    assert_eq!(contact.depth, 0.0);
+    let center = start + motion * found;
+    assert!(contact.point.abs_diff_eq(center + normal * RADIUS, 1e-2), "point {}", contact.point);
+}
+
+#[test]
+fn fast_circle_hits_a_small_circle() {
+    // 500 a frame through a circle 10 across, the end of the move is well past it
+    let obstacle = Collider::Circle { radius: 5.0 };
+    assert_impact(Vec2::ZERO, Vec2::new(500.0, 0.0), &obstacle, at(250.0, 0.0), 225.0 / 500.0, Vec2::X);
+}
+
+#[test]
+fn fast_circle_hits_a_thin_wall() {
+    let wall = Collider::Aabb { half_extents: Vec2::new(2.0, 100.0) };
+    assert_impact(Vec2::ZERO, Vec2::new(1000.0, 0.0), &wall, at(300.0, 0.0), 278.0 / 1000.0, Vec2::X);
+}
+
+#[test]
+fn fast_circle_hits_a_turned_wall() {
+    // a thin wall turned 45 degrees, hit head on along its normal
+    let wall = Collider::Obb { half_extents: Vec2::new(2.0, 100.0) };
+    let normal = Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2);
+    let wall_at = Isometry2d::new(normal * 300.0, Rot2::radians(PI / 4.0));
+    assert_impact(Vec2::ZERO, normal * 1000.0, &wall, wall_at, 278.0 / 1000.0, normal);
+}
+
+#[test]
+fn fast_circle_hits_the_side_of_a_capsule() {
+    let capsule = Collider::Capsule { half_length: 50.0, radius: 3.0 };
+    assert_impact(Vec2::ZERO, Vec2::new(800.0, 0.0), &capsule, at(400.0, 10.0), 377.0 / 800.0, Vec2::X);
+}
+
+#[test]
+fn fast_circle_hits_the_end_of_a_capsule() {
+    // lying along the path, so the round end is what gets hit
+    let capsule = Collider::Capsule { half_length: 50.0, radius: 3.0 };
+    let time = (400.0 - 50.0 - 3.0 - RADIUS) / 800.0;
+    assert_impact(Vec2::ZERO, Vec2::new(0.0, 800.0), &capsule, at(0.0, 400.0), time, Vec2::Y);
+}
+
+#[test]
+fn fast_circle_hits_a_polygon() {
+    let triangle = Collider::Polygon { points: vec![Vec2::new(-5.0, -5.0), Vec2::new(5.0, -5.0), Vec2::new(0.0, 5.0)] };
+    assert_impact(Vec2::ZERO, Vec2::new(0.0, 600.0), &triangle, at(0.0, 300.0), 275.0 / 600.0, Vec2::Y);
+}
+
+#[test]
+fn fast_circle_hits_a_box_corner() {
+    // heading straight at the corner, so the rounded corner of the grown box is what it hits
+    let block = Collider::Aabb { half_extents: Vec2::splat(10.0) };
+    let direction = Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2);
+    let start = Vec2::splat(-200.0);
+    let motion = direction * 600.0;
+    let to_corner = (Vec2::splat(-10.0) - start).length();
+    assert_impact(start, motion, &block, at(0.0, 0.0), (to_corner - RADIUS) / 600.0, direction);
+}
+
+#[test]
+fn passing_by_misses() {
+    let obstacle = Collider::Circle { radius: 5.0 };
+    assert_eq!(time_of_impact(RADIUS, Vec2::ZERO, Vec2::new(500.0, 0.0), &obstacle, at(250.0, 26.0)), None);
+    // moving away from it
+    assert_eq!(time_of_impact(RADIUS, Vec2::ZERO, Vec2::new(-500.0, 0.0), &obstacle, at(250.0, 0.0)), None);
+    // stopping short of it
+    assert_eq!(time_of_impact(RADIUS, Vec2::ZERO, Vec2::new(200.0, 0.0), &obstacle, at(250.0, 0.0)), None);
+}
+
+#[test]
+fn already_overlapping_hits_straight_away() {
+    let obstacle = Collider::Circle { radius: 5.0 };
+    let (time, contact) = time_of_impact(RADIUS, Vec2::ZERO, Vec2::new(500.0, 0.0), &obstacle, at(10.0, 0.0)).unwrap();
+    assert_eq!(time, 0.0);
+    assert!((contact.depth - 15.0).abs() < 1e-4);
+}
+
+#[test]
+fn move_and_slide_stops_at_a_thin_wall() {
+    let player = Collider::Circle { radius: RADIUS };
+    let wall = Collider::Aabb { half_extents: Vec2::new(2.0, 100.0) };
+    let obstacles = [(Entity::from_raw(1), &wall, at(300.0, 0.0))];
+    let slide = move_and_slide(&player, at(0.0, 0.0), Vec2::new(1000.0, 0.0), &obstacles);
+    assert!(slide.position.x <= 278.0 && slide.position.x > 277.9, "ended at {}", slide.position);
+    assert_eq!(slide.hits.len(), 1);
+}
+
+#[test]
+fn move_and_slide_slides_along_a_thin_wall() {
+    // coming in at an angle, the part of the move along the wall is kept
+    let player = Collider::Circle { radius: RADIUS };
+    let wall = Collider::Aabb { half_extents: Vec2::new(2.0, 1000.0) };
+    let obstacles = [(Entity::from_raw(1), &wall, at(300.0, 0.0))];
+    let slide = move_and_slide(&player, at(0.0, 0.0), Vec2::new(1000.0, 500.0), &obstacles);
+    assert!(slide.position.x <= 278.0 && slide.position.x > 277.9, "ended at {}", slide.position);
+    assert!((slide.position.y - 500.0).abs() < 0.1, "ended at {}", slide.position);
+}
+
+fn any_obstacle() -> impl Strategy<Value = Collider> {
+    prop_oneof![
+        (1.0f32..30.0).prop_map(|radius| Collider::Circle { radius }),
+        (1.0f32..30.0, 1.0f32..30.0).prop_map(|(x, y)| Collider::Aabb { half_extents: Vec2::new(x, y) }),
+        (1.0f32..30.0, 1.0f32..30.0).prop_map(|(x, y)| Collider::Obb { half_extents: Vec2::new(x, y) }),
+        (0.0f32..30.0, 1.0f32..10.0).prop_map(|(half_length, radius)| Collider::Capsule { half_length, radius }),
+        (1.0f32..30.0).prop_map(|size| Collider::Polygon {
+            points: vec![Vec2::new(-size, -size), Vec2::new(size, -size), Vec2::new(0.0, size)],
+        }),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    // The time of impact matches walking the circle along the move in tiny steps
+    #[test]
+    fn matches_small_steps(
+        obstacle in any_obstacle(),
+        (x, y, angle) in (-100.0f32..100.0, -100.0f32..100.0, -PI..PI),
+        start_angle in -PI..PI,
+        aim in prop::array::uniform2(-60.0f32..60.0),
+        length in 100.0f32..1000.0,
+    ) {
+        let obstacle_at = Isometry2d::new(Vec2::new(x, y), Rot2::radians(angle));
+        let start = obstacle_at.translation + Vec2::from_angle(start_angle) * 200.0;
+        let motion = (obstacle_at.translation + Vec2::from(aim) - start).normalize() * length;
+        let player = Collider::Circle { radius: RADIUS };
+        let overlaps_at = |time: f32| {
+            collide(&player, Isometry2d::from_translation(start + motion * time), &obstacle, obstacle_at).is_some()
+        };
+        prop_assume!(!overlaps_at(0.0));
+
+        const STEPS: usize = 4000;
+        let sampled = (1..=STEPS).map(|i| i as f32 / STEPS as f32).find(|time| overlaps_at(*time));
+        let found = time_of_impact(RADIUS, start, motion, &obstacle, obstacle_at).map(|(time, _)| time);
+        let step = 1.0 / STEPS as f32;
+        let tolerance = 0.01 / length; // a hundredth of a unit
+
+        match (found, sampled) {
+            (Some(found), Some(sampled)) => {
+                prop_assert!(found <= sampled + tolerance && found >= sampled - step - tolerance,
+                    "found {found}, the steps first overlap at {sampled}");
+            }
+            // the steps can only miss a hit that just grazes the obstacle
+            (Some(found), None) => {
+                let grown = Collider::Circle { radius: RADIUS + 0.01 };
+                let touching = Isometry2d::from_translation(start + motion * found);
+                prop_assert!(collide(&grown, touching, &obstacle, obstacle_at).is_some(), "found {found} but nothing there");
+            }
+            (None, Some(sampled)) => prop_assert!(false, "missed a hit at {sampled}"),
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use blog_common::{FollowPlugin, FollowTarget};
+
+// A camera following a target, with frames of `frame_secs` each
+fn follow(frame_secs: f32, smooth_time: f32) -> (App, Entity, Entity) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, FollowPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(frame_secs)));
+    let target = app.world_mut().spawn(Transform::from_xyz(10.0, 0.0, 0.0)).id();
+    let camera = app
+        .world_mut()
+        .spawn((Transform::default(), FollowTarget { smooth_time, ..FollowTarget::new(target) }))
+        .id();
+    (app, target, camera)
+}
+
+fn x(app: &App, entity: Entity) -> f32 {
+    app.world().get::<Transform>(entity).unwrap().translation.x
+}
+
+#[test]
+fn long_frames_do_not_overshoot() {
+    // frames much longer than the smooth time
+    let (mut app, target, camera) = follow(0.5, 0.05);
+    for _ in 0..10 {
+        app.update();
+        assert!(x(&app, camera) <= x(&app, target), "went past the target to {}", x(&app, camera));
+    }
+    assert_eq!(x(&app, camera), x(&app, target));
+}
+
+#[test]
+fn a_goal_that_jumps_back_is_not_overshot() {
+    let (mut app, target, camera) = follow(1.0 / 60.0, 0.3);
+    // get going towards the target
+    for _ in 0..10 {
+        app.update();
+    }
+    let moving = x(&app, camera);
+    assert!(moving > 0.0 && moving < 10.0, "at {moving}");
+
+    // the target jumps to just in front of the camera, which is still moving fast towards it
+    app.world_mut().get_mut::<Transform>(target).unwrap().translation.x = moving + 0.01;
+    for _ in 0..60 {
+        app.update();
+        assert!(x(&app, camera) <= moving + 0.01, "went past the target to {}", x(&app, camera));
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::{input::InputPlugin, prelude::*};
+use blog_common::{HoverEnter, HoverLeave, PickShape, Pickable, PickingPlugin};
+
+// A headless app with picking, and two spheres sharing one material
+fn scene() -> (App, Entity, Entity, Handle<StandardMaterial>) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), InputPlugin, PickingPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>();
+    let material = app.world_mut().resource_mut::<Assets<StandardMaterial>>().add(StandardMaterial::default());
+    let mut sphere = || {
+        let pickable = Pickable { shape: PickShape::Sphere { radius: 1.0 } };
+        app.world_mut().spawn((Transform::default(), MeshMaterial3d(material.clone()), pickable)).id()
+    };
+    let (hovered, other) = (sphere(), sphere());
+    app.update();
+    (app, hovered, other, material)
+}
+
+fn material_of(app: &App, entity: Entity) -> Handle<StandardMaterial> {
+    app.world().get::<MeshMaterial3d<StandardMaterial>>(entity).unwrap().0.clone()
+}
+
+fn emissive(app: &App, material: &Handle<StandardMaterial>) -> LinearRgba {
+    app.world().resource::<Assets<StandardMaterial>>().get(material).unwrap().emissive
+}
+
+#[test]
+fn only_the_hovered_entity_glows() {
+    let (mut app, hovered, other, shared) = scene();
+    let before = emissive(&app, &shared);
+
+    app.world_mut().send_event(HoverEnter { entity: hovered });
+    app.update();
+    let glowing = material_of(&app, hovered);
+    assert_ne!(glowing, shared);
+    assert_ne!(emissive(&app, &glowing), before);
+    // the material everyone else uses didn't change
+    assert_eq!(material_of(&app, other), shared);
+    assert_eq!(emissive(&app, &shared), before);
+
+    app.world_mut().send_event(HoverLeave { entity: hovered });
+    app.update();
+    assert_eq!(material_of(&app, hovered), shared);
+}
+
+#[test]
+fn a_material_changed_while_hovered_is_kept() {
+    let (mut app, hovered, _, shared) = scene();
+    app.world_mut().send_event(HoverEnter { entity: hovered });
+    app.update();
+
+    let new = app.world_mut().resource_mut::<Assets<StandardMaterial>>().add(StandardMaterial::default());
+    app.world_mut().entity_mut(hovered).insert(MeshMaterial3d(new.clone()));
+    app.world_mut().send_event(HoverLeave { entity: hovered });
+    app.update();
+    assert_eq!(material_of(&app, hovered), new);
+    assert_ne!(material_of(&app, hovered), shared);
+}
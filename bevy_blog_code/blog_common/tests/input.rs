@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use blog_common::{action, context, Binding, InputMap};
+
+// The contexts each example turns on, through its plugins and its own `enable_input_context`
+const EXAMPLES: [(&str, &[&[&str]]); 9] = [
+    ("audio_example", &[context::AUDIO]),
+    ("bevy_blender", &[context::GRID, context::PICKING]),
+    ("clean_split_screen", &[context::GRID, context::FLY_CAMERA, context::PICKING, context::WINDOW]),
+    ("collisions_example_after", &[context::SELECTION, context::EDITOR, context::PLAYER]),
+    (
+        "donut_animation",
+        &[context::GRID, context::FLY_CAMERA, context::BOOKMARKS, context::PICKING, context::SELECTION],
+    ),
+    ("jiggle_sphere", &[context::GRID, context::FLY_CAMERA, context::PICKING, context::JIGGLE]),
+    // without player_dash, which would want Space as well
+    (
+        "simple_game_code",
+        &[context::GRID, &[action::PLAYER_LEFT, action::PLAYER_RIGHT, action::PLAYER_UP, action::PLAYER_DOWN]],
+    ),
+    ("spinny_cube", &[context::GRID, context::PICKING, context::ORBIT_CAMERA]),
+    (
+        "split_screen",
+        &[context::GRID, context::FLY_CAMERA, context::BOOKMARKS, context::PICKING, context::WINDOW],
+    ),
+];
+
+#[test]
+fn default_bindings_do_not_overlap_in_any_example() {
+    for (example, contexts) in EXAMPLES {
+        let mut map = InputMap::default();
+        for actions in contexts {
+            map.enable(actions);
+        }
+        for action in contexts.iter().flat_map(|actions| actions.iter()) {
+            // nothing was taken away because another action already had it
+            assert_eq!(map.bindings(action), map.actions[*action], "{action} in {example}");
+        }
+    }
+}
+
+#[test]
+fn every_default_action_is_in_a_context() {
+    let all = [
+        context::GRID, context::WINDOW, context::FLY_CAMERA, context::ORBIT_CAMERA, context::PICKING,
+        context::SELECTION, context::BOOKMARKS, context::JIGGLE, context::AUDIO, context::PLAYER, context::EDITOR,
+    ];
+    for action in InputMap::default().actions.keys() {
+        assert!(all.iter().any(|actions| actions.contains(&action.as_str())), "{action} can never be read");
+    }
+}
+
+#[test]
+fn a_shared_binding_only_goes_to_the_first_action() {
+    let mut map = InputMap::default();
+    map.enable(context::GRID);
+    map.enable(context::PLAYER);
+    assert_eq!(map.bindings(action::TOGGLE_GRID), map.actions[action::TOGGLE_GRID]);
+    assert_eq!(map.bindings(action::PLAYER_DASH), [Binding::Gamepad(GamepadButton::South)]);
+    // turning a context on twice changes nothing
+    map.enable(context::GRID);
+    assert_eq!(map.bindings(action::TOGGLE_GRID), map.actions[action::TOGGLE_GRID]);
+    assert!(map.bindings(action::JIGGLE).is_empty());
+}
+
+#[test]
+fn held_actions_share_their_keys() {
+    let mut map = InputMap::default();
+    map.enable(context::BOOKMARKS);
+    map.enable(context::FLY_CAMERA);
+    map.enable(context::SELECTION);
+    let ctrl = Binding::Key(KeyCode::ControlLeft);
+    for action in [action::BOOKMARK_SAVE, action::FLY_CRAWL, action::SELECT_TOGGLE] {
+        assert!(map.bindings(action).contains(&ctrl), "{action}");
+    }
+
+    // but not with an action that does something on its own
+    map.actions.insert(action::JIGGLE.to_string(), vec![ctrl]);
+    map.enable(context::JIGGLE);
+    assert!(map.bindings(action::JIGGLE).is_empty());
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+use blog_common::mesh_picking::MeshBvh;
+
+fn cube() -> MeshBvh {
+    MeshBvh::from_mesh(&Mesh::from(Cuboid::new(2.0, 2.0, 2.0))).unwrap()
+}
+
+#[test]
+fn rays_hit_the_nearest_triangle() {
+    let (distance, _, normal) = cube().ray_cast(Vec3::new(0.3, 0.2, 5.0), Vec3::NEG_Z).unwrap();
+    assert!((distance - 4.0).abs() < 1e-5, "{distance}");
+    assert_eq!(normal, Vec3::Z);
+    assert!(cube().ray_cast(Vec3::new(3.0, 0.0, 5.0), Vec3::NEG_Z).is_none());
+}
+
+#[test]
+fn rays_along_the_sides_of_the_boxes() {
+    // straight along the x axis, level with the bottom of the cube, so the ray starts on the plane of
+    // the bottom of every box in the tree and doesn't move up or down at all
+    let hit = cube().ray_cast(Vec3::new(-5.0, -1.0, 0.0), Vec3::X);
+    let (distance, ..) = hit.expect("touches the bottom edge of the left side");
+    assert!((distance - 4.0).abs() < 1e-5, "{distance}");
+}
@@ -0,0 +1,176 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    input::{
+        mouse::MouseButtonInput,
+        touch::{TouchInput, TouchPhase},
+        ButtonState, InputPlugin,
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
+use blog_common::{PointerAction, PointerEvent, PointerId, PointerPlugin, Pointers};
+
+// A headless app with pointers and a window for the mouse and fingers to be over
+fn app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin, PointerPlugin));
+    let window = app.world_mut().spawn((Window::default(), PrimaryWindow)).id();
+    app.update();
+    (app, window)
+}
+
+fn move_cursor(app: &mut App, window: Entity, position: Option<Vec2>) {
+    app.world_mut().get_mut::<Window>(window).unwrap().set_cursor_position(position);
+}
+
+fn click(app: &mut App, window: Entity, state: ButtonState) {
+    app.world_mut().send_event(MouseButtonInput { button: MouseButton::Left, state, window });
+}
+
+fn touch(app: &mut App, window: Entity, id: u64, phase: TouchPhase, x: f32, y: f32) {
+    app.world_mut().send_event(TouchInput { phase, position: Vec2::new(x, y), window, force: None, id });
+}
+
+// The pointer events sent since the last call
+fn events(app: &mut App) -> Vec<(PointerId, PointerAction, Vec2)> {
+    let mut events = app.world_mut().resource_mut::<Events<PointerEvent>>();
+    events.drain().map(|event| (event.id, event.action, event.position)).collect()
+}
+
+#[test]
+fn the_mouse_presses_moves_and_releases() {
+    use PointerAction::*;
+    let (mut app, window) = app();
+    let mouse = PointerId::Mouse;
+
+    move_cursor(&mut app, window, Some(Vec2::new(10.0, 20.0)));
+    app.update();
+    assert_eq!(events(&mut app), [(mouse, Move, Vec2::new(10.0, 20.0))]);
+
+    click(&mut app, window, ButtonState::Pressed);
+    app.update();
+    assert_eq!(events(&mut app), [(mouse, Press, Vec2::new(10.0, 20.0))]);
+
+    move_cursor(&mut app, window, Some(Vec2::new(30.0, 20.0)));
+    app.update();
+    assert_eq!(events(&mut app), [(mouse, Move, Vec2::new(30.0, 20.0))]);
+    let pointer = *app.world().resource::<Pointers>().get(mouse).unwrap();
+    assert_eq!(pointer.delta, Vec2::new(20.0, 0.0));
+    assert!(pointer.pressed);
+    assert_eq!(pointer.press_position, Vec2::new(10.0, 20.0));
+
+    // standing still sends nothing
+    app.update();
+    assert!(events(&mut app).is_empty());
+    assert_eq!(app.world().resource::<Pointers>().get(mouse).unwrap().delta, Vec2::ZERO);
+
+    click(&mut app, window, ButtonState::Released);
+    app.update();
+    assert_eq!(events(&mut app), [(mouse, Release, Vec2::new(30.0, 20.0))]);
+    assert!(!app.world().resource::<Pointers>().get(mouse).unwrap().pressed);
+
+    move_cursor(&mut app, window, None);
+    app.update();
+    assert!(app.world().resource::<Pointers>().get(mouse).is_none());
+}
+
+#[test]
+fn a_drag_out_of_the_window_still_releases() {
+    let (mut app, window) = app();
+    move_cursor(&mut app, window, Some(Vec2::new(10.0, 20.0)));
+    click(&mut app, window, ButtonState::Pressed);
+    app.update();
+    move_cursor(&mut app, window, None);
+    app.update();
+    assert!(app.world().resource::<Pointers>().get(PointerId::Mouse).is_some());
+
+    events(&mut app);
+    click(&mut app, window, ButtonState::Released);
+    app.update();
+    assert_eq!(events(&mut app), [(PointerId::Mouse, PointerAction::Release, Vec2::new(10.0, 20.0))]);
+    assert!(app.world().resource::<Pointers>().get(PointerId::Mouse).is_none());
+}
+
+#[test]
+fn a_finger_presses_moves_and_releases() {
+    use PointerAction::*;
+    let (mut app, window) = app();
+    let finger = PointerId::Touch(7);
+
+    touch(&mut app, window, 7, TouchPhase::Started, 10.0, 10.0);
+    app.update();
+    assert_eq!(events(&mut app), [(finger, Move, Vec2::new(10.0, 10.0)), (finger, Press, Vec2::new(10.0, 10.0))]);
+
+    touch(&mut app, window, 7, TouchPhase::Moved, 15.0, 5.0);
+    app.update();
+    assert_eq!(events(&mut app), [(finger, Move, Vec2::new(15.0, 5.0))]);
+    assert_eq!(app.world().resource::<Pointers>().get(finger).unwrap().delta, Vec2::new(5.0, -5.0));
+
+    touch(&mut app, window, 7, TouchPhase::Ended, 15.0, 5.0);
+    app.update();
+    assert_eq!(events(&mut app), [(finger, Release, Vec2::new(15.0, 5.0))]);
+    assert!(app.world().resource::<Pointers>().get(finger).is_none());
+
+    // the phone taking a finger over counts as lifting it
+    touch(&mut app, window, 8, TouchPhase::Started, 10.0, 10.0);
+    app.update();
+    events(&mut app);
+    touch(&mut app, window, 8, TouchPhase::Canceled, 10.0, 10.0);
+    app.update();
+    assert_eq!(events(&mut app), [(PointerId::Touch(8), Release, Vec2::new(10.0, 10.0))]);
+}
+
+#[test]
+fn two_fingers_pinch_pan_and_twist() {
+    let (mut app, window) = app();
+    let gesture = |app: &App| app.world().resource::<Pointers>().gesture();
+
+    touch(&mut app, window, 1, TouchPhase::Started, 0.0, 0.0);
+    app.update();
+    assert!(gesture(&app).is_none());
+    touch(&mut app, window, 2, TouchPhase::Started, 100.0, 0.0);
+    app.update();
+    let still = gesture(&app).unwrap();
+    assert_eq!((still.pinch, still.pan, still.rotation), (1.0, Vec2::ZERO, 0.0));
+
+    // the second finger moves away, so they are twice as far apart and their middle moved by half of it
+    touch(&mut app, window, 2, TouchPhase::Moved, 200.0, 0.0);
+    app.update();
+    let spread = gesture(&app).unwrap();
+    assert_eq!((spread.pinch, spread.pan, spread.rotation), (2.0, Vec2::new(50.0, 0.0), 0.0));
+
+    // a quarter turn around the first finger
+    touch(&mut app, window, 2, TouchPhase::Moved, 0.0, 200.0);
+    app.update();
+    let twist = gesture(&app).unwrap();
+    assert!((twist.pinch - 1.0).abs() < 1e-6);
+    assert!((twist.rotation - FRAC_PI_2).abs() < 1e-6, "turned {}", twist.rotation);
+
+    // a third finger stops the gesture, lifting it starts it again
+    touch(&mut app, window, 3, TouchPhase::Started, 50.0, 50.0);
+    app.update();
+    assert!(gesture(&app).is_none());
+    touch(&mut app, window, 3, TouchPhase::Ended, 50.0, 50.0);
+    app.update();
+    assert!(gesture(&app).is_some());
+}
+
+#[test]
+fn fingers_meeting_on_one_spot_do_not_pinch_to_nothing() {
+    let (mut app, window) = app();
+    touch(&mut app, window, 1, TouchPhase::Started, 0.0, 0.0);
+    touch(&mut app, window, 2, TouchPhase::Started, 100.0, 0.0);
+    app.update();
+
+    touch(&mut app, window, 2, TouchPhase::Moved, 0.0, 0.0);
+    app.update();
+    let together = app.world().resource::<Pointers>().gesture().unwrap();
+    assert_eq!((together.pinch, together.rotation), (1.0, 0.0));
+
+    // and moving apart again from there
+    touch(&mut app, window, 2, TouchPhase::Moved, 50.0, 0.0);
+    app.update();
+    let apart = app.world().resource::<Pointers>().gesture().unwrap();
+    assert_eq!((apart.pinch, apart.rotation), (1.0, 0.0));
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use blog_common::picking::{ray_cast, ray_torus, PickShape};
+use proptest::prelude::*;
+
+// How far a point is from the surface of a torus lying flat around the Y axis, negative inside
+fn torus_distance(point: Vec3, major_radius: f32, minor_radius: f32) -> f32 {
+    let ring = Vec2::new(point.x, point.z).length() - major_radius;
+    Vec2::new(ring, point.y).length() - minor_radius
+}
+
+// The slow way: walk along the ray in tiny steps until it goes inside, then bisect the last step.
+// Also returns how deep the ray gets into the torus, to tell real hits from rays that only graze it.
+fn sampled_hit(distance_at: impl Fn(f32) -> f32, length: f32) -> (Option<f32>, f32) {
+    const STEPS: usize = 20_000;
+    let step = length / STEPS as f32;
+    let mut deepest = f32::INFINITY;
+    let mut hit = None;
+    for i in 1..=STEPS {
+        let t = i as f32 * step;
+        deepest = deepest.min(distance_at(t));
+        if hit.is_none() && distance_at(t) <= 0.0 {
+            let (mut outside, mut inside) = (t - step, t);
+            for _ in 0..40 {
+                let middle = (outside + inside) / 2.0;
+                if distance_at(middle) <= 0.0 {
+                    inside = middle;
+                } else {
+                    outside = middle;
+                }
+            }
+            hit = Some(inside);
+        }
+    }
+    (hit, deepest)
+}
+
+fn unit_vector(yaw: f32, pitch: f32) -> Vec3 {
+    Vec3::new(pitch.cos() * yaw.cos(), pitch.sin(), pitch.cos() * yaw.sin())
+}
+
+#[test]
+fn straight_down_onto_the_ring() {
+    let t = ray_torus(Vec3::new(0.5, 5.0, 0.0), Vec3::NEG_Y, 0.5, 0.2).unwrap();
+    assert!((t - 4.8).abs() < 1e-5, "{t}");
+}
+
+#[test]
+fn through_the_hole_misses() {
+    assert_eq!(ray_torus(Vec3::new(0.0, 5.0, 0.0), Vec3::NEG_Y, 0.5, 0.2), None);
+}
+
+#[test]
+fn along_the_axis_of_symmetry_hits_the_near_side() {
+    // goes in and out of the torus twice, the first entry is the one that counts
+    let t = ray_torus(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 0.5, 0.2).unwrap();
+    assert!((t - 4.3).abs() < 1e-5, "{t}");
+}
+
+#[test]
+fn from_inside_the_tube_hits_the_way_out() {
+    let t = ray_torus(Vec3::new(0.5, 0.0, 0.0), Vec3::Y, 0.5, 0.2).unwrap();
+    assert!((t - 0.2).abs() < 1e-5, "{t}");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(400))]
+
+    // Aims random rays near a torus that is moved, turned and scaled, and checks the exact answer
+    // against walking along the ray
+    #[test]
+    fn matches_sampled_reference(
+        major_radius in 0.3f32..2.0,
+        thickness in 0.05f32..0.95,
+        (view_yaw, view_pitch) in (0.0f32..std::f32::consts::TAU, -1.5f32..1.5),
+        view_distance in 2.0f32..6.0,
+        aim in prop::array::uniform3(-1.0f32..1.0),
+        (spin_x, spin_y, spin_z) in (-3.2f32..3.2, -3.2f32..3.2, -3.2f32..3.2),
+        position in prop::array::uniform3(-5.0f32..5.0),
+        scale in 0.5f32..2.0,
+    ) {
+        let minor_radius = major_radius * thickness;
+        let size = major_radius + minor_radius;
+        let transform = Transform::from_translation(Vec3::from(position))
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, spin_x, spin_y, spin_z))
+            .with_scale(Vec3::splat(scale));
+        let global = GlobalTransform::from(transform);
+
+        // from outside the torus, pointed at a random spot inside its bounding box
+        let origin = transform.transform_point(unit_vector(view_yaw, view_pitch) * size * view_distance);
+        let target = transform.transform_point(Vec3::from(aim) * size);
+        let ray = Ray3d::new(origin, Dir3::new(target - origin).unwrap());
+
+        let to_local = global.affine().inverse();
+        let distance_at = |t: f32| {
+            torus_distance(to_local.transform_point3(ray.get_point(t)), major_radius, minor_radius) * scale
+        };
+        // far enough to come out the other side of the bounding sphere, wherever the target is
+        let length = (origin - transform.translation).length() + size * scale;
+        let (expected, deepest) = sampled_hit(distance_at, length);
+
+        let shape = PickShape::Torus { major_radius, minor_radius };
+        let found = ray_cast(ray, &global, &shape).map(|(t, _)| t);
+        let tolerance = 1e-3 * size * scale;
+
+        match (found, expected) {
+            (Some(found), Some(expected)) => {
+                prop_assert!((found - expected).abs() < tolerance, "found {found}, expected {expected}");
+            }
+            // the walk steps over rays that only just touch the torus, the exact test may still see those
+            (Some(found), None) => {
+                prop_assert!(distance_at(found).abs() < tolerance, "found {found} is not on the surface");
+                prop_assert!(deepest < tolerance, "found {found} but the ray never gets close");
+            }
+            (None, Some(expected)) => {
+                prop_assert!(deepest > -tolerance, "missed a hit at {expected}");
+            }
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use blog_common::{Random, SpatialHash};
+use proptest::prelude::*;
+
+const CELL: f32 = 10.0;
+
+fn entity(index: u32) -> Entity {
+    Entity::from_raw(index)
+}
+
+fn square(x: f32, y: f32, size: f32) -> Rect {
+    Rect::from_center_size(Vec2::new(x, y), Vec2::splat(size))
+}
+
+fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+    entities.sort();
+    entities
+}
+
+#[test]
+fn inserted_entities_are_found_where_they_are() {
+    let mut hash = SpatialHash::new(CELL);
+    hash.insert(entity(1), square(5.0, 5.0, 2.0));
+    hash.insert(entity(2), square(-25.0, 5.0, 2.0));
+    assert_eq!(hash.len(), 2);
+    assert_eq!(hash.bounds(entity(1)), Some(square(5.0, 5.0, 2.0)));
+
+    assert_eq!(hash.query(square(5.0, 5.0, 1.0)), [entity(1)]);
+    assert_eq!(hash.query(square(-25.0, 5.0, 1.0)), [entity(2)]);
+    assert_eq!(sorted(hash.query(square(-10.0, 5.0, 40.0))), [entity(1), entity(2)]);
+    // in the same cell, but its box is somewhere else in it
+    assert!(hash.query(square(1.0, 1.0, 1.0)).is_empty());
+    assert!(hash.query(square(100.0, 100.0, 1.0)).is_empty());
+}
+
+#[test]
+fn an_entity_over_several_cells_is_found_once() {
+    let mut hash = SpatialHash::new(CELL);
+    // covers cells -2 to 2 both ways
+    hash.insert(entity(1), square(0.0, 0.0, 45.0));
+    assert_eq!(hash.query(square(0.0, 0.0, 100.0)), [entity(1)]);
+    assert_eq!(hash.query(square(21.0, -21.0, 2.0)), [entity(1)]);
+}
+
+#[test]
+fn moving_across_cells_leaves_the_old_ones() {
+    let mut hash = SpatialHash::new(CELL);
+    hash.insert(entity(1), square(5.0, 5.0, 2.0));
+    hash.insert(entity(1), square(55.0, -35.0, 2.0));
+    assert_eq!(hash.len(), 1);
+    assert!(hash.query(square(5.0, 5.0, 4.0)).is_empty());
+    assert_eq!(hash.query(square(55.0, -35.0, 4.0)), [entity(1)]);
+
+    // moving inside the same cell still updates the box
+    hash.insert(entity(1), square(52.0, -38.0, 2.0));
+    assert!(hash.query(square(55.0, -35.0, 1.0)).is_empty());
+    assert_eq!(hash.query(square(52.0, -38.0, 1.0)), [entity(1)]);
+
+    // growing into more cells and shrinking back
+    hash.insert(entity(1), square(50.0, -40.0, 30.0));
+    assert_eq!(hash.query(square(62.0, -28.0, 1.0)), [entity(1)]);
+    hash.insert(entity(1), square(52.0, -38.0, 2.0));
+    assert!(hash.query(square(62.0, -28.0, 1.0)).is_empty());
+}
+
+#[test]
+fn removed_entities_are_gone() {
+    let mut hash = SpatialHash::new(CELL);
+    hash.insert(entity(1), square(0.0, 0.0, 25.0));
+    hash.insert(entity(2), square(0.0, 0.0, 2.0));
+    hash.remove(entity(1));
+    assert_eq!(hash.len(), 1);
+    assert_eq!(hash.bounds(entity(1)), None);
+    assert_eq!(hash.query(square(0.0, 0.0, 100.0)), [entity(2)]);
+    // removing it again, or something that was never in, does nothing
+    hash.remove(entity(1));
+    hash.remove(entity(3));
+    assert_eq!(hash.len(), 1);
+
+    hash.clear();
+    assert!(hash.is_empty());
+    assert!(hash.query(square(0.0, 0.0, 100.0)).is_empty());
+}
+
+#[test]
+fn endless_regions_find_everything() {
+    let mut hash = SpatialHash::new(CELL);
+    hash.insert(entity(1), square(-1e6, 0.0, 2.0));
+    hash.insert(entity(2), square(1e6, 1e6, 2.0));
+    let everywhere = Rect { min: Vec2::splat(f32::NEG_INFINITY), max: Vec2::splat(f32::INFINITY) };
+    assert_eq!(sorted(hash.query(everywhere)), [entity(1), entity(2)]);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    // After lots of inserts, moves and removes, queries find the same as checking every box
+    #[test]
+    fn queries_match_checking_every_box(seed in 1u32..u32::MAX) {
+        let mut random = Random(seed);
+        let mut hash = SpatialHash::new(CELL);
+        let mut boxes: Vec<Option<Rect>> = vec![None; 20];
+        for _ in 0..200 {
+            let index = random.range(0.0, boxes.len() as f32) as usize;
+            if random.range(0.0, 1.0) < 0.2 {
+                hash.remove(entity(index as u32));
+                boxes[index] = None;
+            } else {
+                let bounds = square(random.range(-50.0, 50.0), random.range(-50.0, 50.0), random.range(0.0, 30.0));
+                hash.insert(entity(index as u32), bounds);
+                boxes[index] = Some(bounds);
+            }
+
+            let region = square(random.range(-60.0, 60.0), random.range(-60.0, 60.0), random.range(0.0, 40.0));
+            let expected: Vec<Entity> = boxes
+                .iter()
+                .enumerate()
+                // touching edges count as overlapping
+                .filter(|(_, bounds)| {
+                    bounds.is_some_and(|bounds| bounds.min.cmple(region.max).all() && region.min.cmple(bounds.max).all())
+                })
+                .map(|(index, _)| entity(index as u32))
+                .collect();
+            prop_assert_eq!(sorted(hash.query(region)), expected);
+            prop_assert_eq!(hash.len(), boxes.iter().flatten().count());
+        }
+    }
+}
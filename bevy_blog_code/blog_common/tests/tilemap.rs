@@ -0,0 +1,156 @@
+use bevy::{asset::LoadState, prelude::*};
+use blog_common::{test_support::load_asset, Collider, Tile, Tilemap, TilemapPlugin, LOADED_TILE_SIZE};
+
+const TILE: f32 = 50.0;
+const PLAYER: Collider = Collider::Circle { radius: 20.0 };
+
+// A room with a wall down the middle and a hazard in the bottom right
+const ROOM: &str = "\
+#######
+#@.#..#
+#..#..#
+#....^#
+#######
+";
+
+fn at(point: Vec2) -> Isometry2d {
+    Isometry2d::from_translation(point)
+}
+
+#[test]
+fn text_maps_start_at_the_top() {
+    let map = Tilemap::parse(ROOM, TILE).unwrap();
+    assert_eq!((map.width(), map.height()), (7, 5));
+    // the last line is the bottom row
+    assert_eq!(map.get(IVec2::new(5, 1)), Some(Tile::Hazard));
+    assert_eq!(map.get(IVec2::new(3, 3)), Some(Tile::Solid));
+    assert_eq!(map.get(IVec2::new(3, 1)), Some(Tile::Empty));
+    assert_eq!(map.spawn, Some(UVec2::new(1, 3)));
+    assert_eq!(map.get(IVec2::new(1, 3)), Some(Tile::Empty));
+    assert_eq!(map.get(IVec2::new(7, 0)), None);
+    assert_eq!(map.get(IVec2::new(0, -1)), None);
+}
+
+#[test]
+fn csv_maps_read_the_same() {
+    let csv = "1,1,1,1\r\n1, 0, 2,1\r\n\r\n1,@,0,1\r\n1,1,1,1\r\n";
+    let text = "####\n#.^#\n#@.#\n####\n";
+    let (from_csv, from_text) = (Tilemap::parse(csv, TILE).unwrap(), Tilemap::parse(text, TILE).unwrap());
+    assert_eq!(from_csv.spawn, from_text.spawn);
+    for y in 0..4 {
+        for x in 0..4 {
+            let tile = IVec2::new(x, y);
+            assert_eq!(from_csv.get(tile), from_text.get(tile), "tile {tile}");
+        }
+    }
+}
+
+#[test]
+fn bad_maps_say_where_the_problem_is() {
+    let unknown = Tilemap::parse("###\n#x#\n###", TILE).unwrap_err();
+    assert_eq!((unknown.line, unknown.column), (2, 2));
+    assert!(unknown.to_string().contains("`x` isn't a tile"), "{unknown}");
+
+    let ragged = Tilemap::parse("####\n##\n####", TILE).unwrap_err();
+    assert_eq!(ragged.line, 2);
+    assert!(ragged.to_string().contains("2 tiles wide"), "{ragged}");
+
+    let two_starts = Tilemap::parse("@.\n.@", TILE).unwrap_err();
+    assert_eq!((two_starts.line, two_starts.column), (2, 2));
+
+    assert!(Tilemap::parse("\n\n", TILE).is_err());
+}
+
+#[test]
+fn tiles_are_placed_from_the_origin() {
+    let mut map = Tilemap::parse(ROOM, TILE).unwrap();
+    map.origin = Vec2::new(-100.0, 200.0);
+    assert_eq!(map.bounds(), Rect::new(-100.0, 200.0, 250.0, 450.0));
+    assert_eq!(map.tile_at(Vec2::new(-99.0, 201.0)), IVec2::ZERO);
+    assert_eq!(map.tile_at(Vec2::new(-101.0, 201.0)), IVec2::new(-1, 0));
+    assert_eq!(map.tile_center(IVec2::new(1, 2)), Vec2::new(-25.0, 325.0));
+}
+
+#[test]
+fn walls_stop_the_player() {
+    let map = Tilemap::parse(ROOM, TILE).unwrap();
+    // from the middle of the left room straight at the middle wall, which starts at x = 150
+    let start = map.tile_center(IVec2::new(1, 2));
+    let slide = map.move_and_slide(&PLAYER, at(start), Vec2::new(200.0, 0.0));
+    assert!(slide.position.x <= 130.0 && slide.position.x > 129.9, "ended at {}", slide.position);
+    assert_eq!(slide.hits.len(), 1);
+    assert_eq!(slide.hits[0].0, IVec2::new(3, 2));
+}
+
+#[test]
+fn fast_moves_do_not_pass_through_walls() {
+    let map = Tilemap::parse(ROOM, TILE).unwrap();
+    let start = map.tile_center(IVec2::new(1, 2));
+    let slide = map.move_and_slide(&PLAYER, at(start), Vec2::new(5000.0, 0.0));
+    assert!(slide.position.x <= 130.0, "ended at {}", slide.position);
+}
+
+#[test]
+fn sliding_along_a_wall_does_not_catch_on_the_seams() {
+    // a long straight wall made of many separate tiles
+    let map = Tilemap::parse("##########\n..........\n..........", TILE).unwrap();
+    let start = Vec2::new(30.0, 80.0); // touching the underside of the wall
+    let mut position = start;
+    for _ in 0..40 {
+        // pushing up into the wall while moving right
+        position = map.move_and_slide(&PLAYER, at(position), Vec2::new(10.0, 5.0)).position;
+    }
+    assert!((position.y - 80.0).abs() < 0.1, "ended at {position}");
+    assert!(position.x > start.x + 399.0, "only got to {position}");
+}
+
+#[test]
+fn the_edge_of_the_map_is_a_wall() {
+    let map = Tilemap::parse("...\n...\n...", TILE).unwrap();
+    let slide = map.move_and_slide(&PLAYER, at(Vec2::splat(75.0)), Vec2::new(-500.0, 0.0));
+    assert!((slide.position.x - 20.0).abs() < 0.1, "ended at {}", slide.position);
+    assert_eq!(slide.hits[0].0, IVec2::new(-1, 1));
+}
+
+#[test]
+fn touching_a_hazard() {
+    let map = Tilemap::parse(ROOM, TILE).unwrap();
+    let hazard = map.tile_center(IVec2::new(5, 1));
+    assert!(map.touches(Tile::Hazard, &PLAYER, at(hazard - Vec2::new(40.0, 0.0))));
+    // its bounding box reaches the hazard tile, but the circle doesn't
+    assert!(!map.touches(Tile::Hazard, &PLAYER, at(hazard + Vec2::new(-40.0, 40.0))));
+    assert!(!map.touches(Tile::Hazard, &PLAYER, at(map.tile_center(IVec2::new(1, 2)))));
+}
+
+// Loads one of the maps in tests/maps through the asset server, the way a game does
+fn load(file: &str) -> (App, Handle<Tilemap>, LoadState) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin { file_path: "tests/maps".to_string(), ..default() }))
+        .add_plugins(TilemapPlugin)
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>();
+    let (handle, state) = load_asset(&mut app, file);
+    (app, handle, state)
+}
+
+#[test]
+fn map_files_load_as_assets() {
+    for file in ["room.map.txt", "room.map.csv"] {
+        let (app, handle, state) = load(file);
+        assert!(matches!(state, LoadState::Loaded), "{file}: {state:?}");
+        let map = app.world().resource::<Assets<Tilemap>>().get(&handle).unwrap();
+        assert_eq!((map.width(), map.height()), (5, 3), "{file}");
+        assert_eq!(map.tile_size, LOADED_TILE_SIZE);
+        assert_eq!(map.spawn, Some(UVec2::new(1, 1)), "{file}");
+        assert_eq!(map.get(IVec2::new(3, 1)), Some(Tile::Hazard), "{file}");
+    }
+}
+
+#[test]
+fn bad_map_files_say_where_the_problem_is() {
+    let (_, _, state) = load("typo.map.txt");
+    let LoadState::Failed(err) = state else { panic!("the map should not load, it's {state:?}") };
+    let err = err.to_string();
+    assert!(err.contains("line 2, column 4"), "{err}");
+    assert!(err.contains("`x` isn't a tile"), "{err}");
+}
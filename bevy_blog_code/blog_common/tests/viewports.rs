@@ -0,0 +1,75 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    prelude::*,
+    render::camera::{camera_system, ManualTextureViews, Projection, Viewport},
+    window::{PrimaryWindow, WindowCreated, WindowResized, WindowResolution, WindowScaleFactorChanged},
+};
+use blog_common::picking::camera_under_cursor;
+
+// A headless 800 by 600 window with only the part of the renderer that works out where each camera draws
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_resource::<ManualTextureViews>()
+        .add_event::<WindowCreated>()
+        .add_event::<WindowResized>()
+        .add_event::<WindowScaleFactorChanged>()
+        .add_systems(PostUpdate, camera_system::<Projection>);
+    let window = Window { resolution: WindowResolution::new(800.0, 600.0), ..default() };
+    app.world_mut().spawn((window, PrimaryWindow));
+    app
+}
+
+fn camera(app: &mut App, order: isize, viewport: Option<(UVec2, UVec2)>) -> Entity {
+    let viewport =
+        viewport.map(|(physical_position, physical_size)| Viewport { physical_position, physical_size, ..default() });
+    app.world_mut().spawn((Camera3d::default(), Camera { order, viewport, ..default() })).id()
+}
+
+// Which camera is under the cursor, told apart by their order and size, and where the cursor is in it
+fn under(app: &mut App, cursor: Vec2) -> Option<(isize, Vec2, Vec2)> {
+    app.world_mut()
+        .run_system_once(move |cameras: Query<(&Camera, &GlobalTransform)>| {
+            camera_under_cursor(cursor, &cameras)
+                .map(|(camera, _, position)| (camera.order, camera.logical_viewport_size().unwrap(), position))
+        })
+        .unwrap()
+}
+
+#[test]
+fn the_camera_under_the_cursor_is_found() {
+    let mut app = app();
+    camera(&mut app, 0, None);
+    camera(&mut app, 0, Some((UVec2::new(400, 0), UVec2::new(400, 600))));
+    app.update();
+
+    assert_eq!(under(&mut app, Vec2::new(100.0, 100.0)), Some((0, Vec2::new(800.0, 600.0), Vec2::new(100.0, 100.0))));
+    // the position is inside the viewport, from its top left
+    assert_eq!(under(&mut app, Vec2::new(500.0, 100.0)), Some((0, Vec2::new(400.0, 600.0), Vec2::new(100.0, 100.0))));
+    assert_eq!(under(&mut app, Vec2::new(900.0, 100.0)), None);
+}
+
+#[test]
+fn overlapping_viewports_go_by_order_then_size() {
+    let mut app = app();
+    // the whole window, a big view drawn on top of its top left, and a small one under that
+    camera(&mut app, 0, None);
+    camera(&mut app, 1, Some((UVec2::ZERO, UVec2::new(400, 300))));
+    camera(&mut app, 0, Some((UVec2::new(50, 50), UVec2::new(100, 100))));
+    // and a picture in picture in the top right, with the same order as the whole window
+    camera(&mut app, 0, Some((UVec2::new(600, 0), UVec2::new(200, 150))));
+    app.update();
+
+    // the higher order wins, even over a smaller viewport
+    let (order, size, position) = under(&mut app, Vec2::new(100.0, 100.0)).unwrap();
+    assert_eq!((order, size, position), (1, Vec2::new(400.0, 300.0), Vec2::new(100.0, 100.0)));
+
+    // with the same order, the smaller one is on top
+    let (order, size, position) = under(&mut app, Vec2::new(700.0, 100.0)).unwrap();
+    assert_eq!((order, size, position), (0, Vec2::new(200.0, 150.0), Vec2::new(100.0, 100.0)));
+
+    // and nothing else there leaves the whole window
+    let (order, size, _) = under(&mut app, Vec2::new(700.0, 500.0)).unwrap();
+    assert_eq!((order, size), (0, Vec2::new(800.0, 600.0)));
+}
@@ -0,0 +1,439 @@
+use bevy::{asset::AssetLoadFailedEvent, color::palettes::basic::*, input::InputSystem, prelude::*, window::PrimaryWindow};
+use blog_common::{
+    action, collide, context, ActionState, Collider, EnableInputContext, Selectable, Selected, SelectionBlocked,
+};
+
+use crate::{
+    level::{respawn_obstacles, CurrentLevel, FromLevel, Level, LevelObstacle, LEVEL_PATH},
+    selection_box, spawn_obstacle, Obstacle,
+};
+
+// Press Tab to edit the level while the game runs. Click on empty space to place an obstacle, drag one
+// to move it, and drag the white square on a selected one to resize it. Clicking and dragging a rectangle
+// selects, like everywhere else. Delete removes the selection, Ctrl+Z and Ctrl+Y undo and redo,
+// and Ctrl+S and Ctrl+O save the level to its file and load it back.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.enable_input_context(context::EDITOR)
+            .init_resource::<Editor>()
+            .init_resource::<EditorBusy>()
+            .init_resource::<SelectionBlocked>()
+            .add_systems(Startup, spawn_help)
+            // before the selection looks at what the mouse button went down on
+            .add_systems(PreUpdate, hover_obstacle.after(InputSystem))
+            .add_systems(
+                Update,
+                (
+                    toggle_editor,
+                    edit_obstacles,
+                    delete_selected,
+                    undo_redo,
+                    save_and_load,
+                    record_load,
+                    draw_handles,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// A click that moves less than this many pixels places an obstacle, more than that is a selection rectangle
+const CLICK_SIZE: f32 = 4.0;
+// How close to a handle the cursor has to be to grab it
+const HANDLE_SIZE: f32 = 6.0;
+// The smallest an obstacle can be made, so it can always be grabbed again
+const MIN_SIZE: f32 = 5.0;
+// How big a newly placed obstacle is
+const NEW_RADIUS: f32 = 30.0;
+// Edits kept for undo, the oldest are dropped after this
+const UNDO_LIMIT: usize = 100;
+
+#[derive(Resource, Default)]
+pub struct Editor {
+    pub enabled: bool,
+    drag: Option<EditDrag>,
+    undo: Vec<Vec<LevelObstacle>>, // the level's obstacles before each edit
+    redo: Vec<Vec<LevelObstacle>>,
+    loading: Option<Vec<LevelObstacle>>, // the obstacles from before a load that hasn't finished yet
+}
+
+impl Editor {
+    // Call with the obstacles as they were before an edit. A new edit can't be redone past, so that's cleared.
+    pub fn record(&mut self, before: Vec<LevelObstacle>) {
+        self.undo.push(before);
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    // The obstacles to go back to, if there's anything to undo. `now` is kept so it can be redone.
+    pub fn undo(&mut self, now: Vec<LevelObstacle>) -> Option<Vec<LevelObstacle>> {
+        let level = self.undo.pop()?;
+        self.redo.push(now);
+        Some(level)
+    }
+
+    // The same the other way around
+    pub fn redo(&mut self, now: Vec<LevelObstacle>) -> Option<Vec<LevelObstacle>> {
+        let level = self.redo.pop()?;
+        self.undo.push(now);
+        Some(level)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+// The obstacle or handle under the cursor that a press would grab, while editing
+#[derive(Resource, Default)]
+pub struct EditorBusy(pub Option<Entity>);
+
+// What the mouse button is doing, from going down until it's let go
+struct EditDrag {
+    before: Vec<LevelObstacle>, // only recorded for undo if something actually changed
+    start: Vec2,                // where the cursor went down, in the window
+    kind: DragKind,
+}
+
+enum DragKind {
+    Empty, // nothing under the cursor, a click there places an obstacle
+    Move { grab: Vec2, starts: Vec<(Entity, Vec2)> },
+    Resize { entity: Entity, shape: Collider }, // the shape as it was when the handle was grabbed
+}
+
+// Shows which keys do what while editing
+#[derive(Component)]
+struct EditorHelp;
+
+fn spawn_help(mut commands: Commands) {
+    commands.spawn((
+        EditorHelp,
+        Text::new(
+            "EDITING (Tab to play)\n\
+             Click: place   Drag: move   Drag white square: resize\n\
+             Delete: remove   Ctrl+Z / Ctrl+Y: undo / redo   Ctrl+S / Ctrl+O: save / load",
+        ),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_editor(
+    actions: Res<ActionState>,
+    mut editor: ResMut<Editor>,
+    mut help: Query<&mut Visibility, With<EditorHelp>>,
+) {
+    if !actions.just_pressed(action::EDITOR_TOGGLE) {
+        return;
+    }
+    editor.enabled = !editor.enabled;
+    editor.drag = None;
+    for mut visibility in &mut help {
+        *visibility = if editor.enabled { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+// The level's obstacles as they are now, in the form they're saved in
+fn snapshot<'a>(obstacles: impl Iterator<Item = (&'a Obstacle, &'a Collider)>) -> Vec<LevelObstacle> {
+    obstacles
+        .map(|(obstacle, shape)| LevelObstacle {
+            position: obstacle.position,
+            angle: obstacle.angle.to_degrees(),
+            shape: shape.clone(),
+        })
+        .collect()
+}
+
+fn cursor_in_world(window: &Window, camera: &Camera, camera_transform: &GlobalTransform) -> Option<(Vec2, Vec2)> {
+    let cursor = window.cursor_position()?;
+    let point = camera.viewport_to_world_2d(camera_transform, cursor).ok()?;
+    Some((cursor, point))
+}
+
+// The handle sits on the corner of the shape, in the obstacle's own space
+fn handle_offset(shape: &Collider) -> Vec2 {
+    match shape {
+        Collider::Circle { radius } => Vec2::new(*radius, 0.0),
+        Collider::Aabb { half_extents } | Collider::Obb { half_extents } => *half_extents,
+        Collider::Capsule { half_length, radius } => Vec2::new(*radius, half_length + radius),
+        Collider::Polygon { .. } => shape.bounds(Isometry2d::IDENTITY).max,
+    }
+}
+
+// `Aabb` boxes don't turn, so neither does their handle
+fn handle_position(obstacle: &Obstacle, shape: &Collider) -> Vec2 {
+    match shape {
+        Collider::Aabb { .. } => obstacle.position + handle_offset(shape),
+        _ => obstacle.isometry() * handle_offset(shape),
+    }
+}
+
+// The shape with its handle moved to `point`, which is in the obstacle's own space
+fn resized(shape: &Collider, point: Vec2) -> Collider {
+    let corner = point.abs().max(Vec2::splat(MIN_SIZE));
+    let distance = point.length().max(MIN_SIZE);
+    match shape {
+        Collider::Circle { .. } => Collider::Circle { radius: distance },
+        Collider::Aabb { .. } => Collider::Aabb { half_extents: corner },
+        Collider::Obb { .. } => Collider::Obb { half_extents: corner },
+        Collider::Capsule { .. } => {
+            Collider::Capsule { half_length: (corner.y - corner.x).max(0.0), radius: corner.x }
+        }
+        // polygons keep their shape and grow or shrink evenly
+        Collider::Polygon { points } => {
+            let scale = distance / handle_offset(shape).length().max(MIN_SIZE);
+            Collider::Polygon { points: points.iter().map(|point| *point * scale).collect() }
+        }
+    }
+}
+
+// Finds what a press would grab, and lets the selection know, so pressing on an obstacle or a handle drags it
+// instead of starting a selection rectangle
+fn hover_obstacle(
+    editor: Res<Editor>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    obstacles: Query<(Entity, &Obstacle, &Collider, Has<Selected>), With<FromLevel>>,
+    mut busy: ResMut<EditorBusy>,
+    mut blocked: ResMut<SelectionBlocked>,
+) {
+    let (camera, camera_transform) = *camera;
+    let cursor = windows.get_single().ok().and_then(|window| cursor_in_world(window, camera, camera_transform));
+    let found = match cursor {
+        Some((_, point)) if editor.enabled => {
+            let on_handle = obstacles.iter().find(|(_, obstacle, shape, selected)| {
+                *selected && handle_position(obstacle, shape).distance(point) < HANDLE_SIZE
+            });
+            let pointer = Collider::Circle { radius: 1.0 };
+            let on_shape = || {
+                obstacles.iter().find(|(_, obstacle, shape, _)| {
+                    collide(&pointer, Isometry2d::from_translation(point), shape, obstacle.isometry()).is_some()
+                })
+            };
+            on_handle.or_else(on_shape).map(|(entity, ..)| entity)
+        }
+        _ => None,
+    };
+    if busy.0 != found {
+        busy.0 = found;
+    }
+    if blocked.0 != found.is_some() {
+        blocked.0 = found.is_some();
+    }
+}
+
+fn edit_obstacles(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    busy: Res<EditorBusy>,
+    mut editor: ResMut<Editor>,
+    mut obstacles: Query<
+        (Entity, &mut Obstacle, &mut Collider, &mut Transform, &mut Selectable, Has<Selected>),
+        With<FromLevel>,
+    >,
+) {
+    if !editor.enabled {
+        return;
+    }
+    let Ok(window) = windows.get_single() else { return; };
+    let (camera, camera_transform) = *camera;
+    let cursor = cursor_in_world(window, camera, camera_transform);
+
+    if actions.just_pressed(action::PICK) {
+        let Some((start, point)) = cursor else { return; };
+        let before = snapshot(obstacles.iter().map(|(_, obstacle, shape, ..)| (obstacle, shape)));
+        let grabbed = busy.0.and_then(|entity| obstacles.get(entity).ok());
+        let kind = match grabbed {
+            Some((entity, obstacle, shape, _, _, selected)) => {
+                if selected && handle_position(obstacle, shape).distance(point) < HANDLE_SIZE {
+                    DragKind::Resize { entity, shape: shape.clone() }
+                } else if selected {
+                    // grabbing one of the selected obstacles moves all of them
+                    let selected = obstacles.iter().filter(|item| item.5);
+                    let starts = selected.map(|item| (item.0, item.1.position)).collect();
+                    DragKind::Move { grab: point, starts }
+                } else {
+                    DragKind::Move { grab: point, starts: vec![(entity, obstacle.position)] }
+                }
+            }
+            None => DragKind::Empty,
+        };
+        editor.drag = Some(EditDrag { before, start, kind });
+    }
+
+    let Some(drag) = &editor.drag else { return; };
+    if let Some((_, point)) = cursor {
+        match &drag.kind {
+            DragKind::Empty => {}
+            DragKind::Move { grab, starts } => {
+                for (entity, start) in starts {
+                    let Ok((_, mut obstacle, _, mut transform, ..)) = obstacles.get_mut(*entity) else { continue; };
+                    obstacle.position = *start + point - *grab;
+                    transform.translation = obstacle.position.extend(0.0);
+                }
+            }
+            DragKind::Resize { entity, shape } => {
+                if let Ok((_, obstacle, mut collider, _, mut selectable, _)) = obstacles.get_mut(*entity) {
+                    let local = match shape {
+                        Collider::Aabb { .. } => point - obstacle.position,
+                        _ => obstacle.isometry().inverse() * point,
+                    };
+                    *collider = resized(shape, local);
+                    *selectable = selection_box(&collider);
+                }
+            }
+        }
+    }
+    if actions.pressed(action::PICK) {
+        return;
+    }
+
+    // the button was let go
+    let Some(drag) = editor.drag.take() else { return; };
+    match drag.kind {
+        DragKind::Empty => {
+            let Some((end, point)) = cursor else { return; };
+            if (end - drag.start).abs().max_element() < CLICK_SIZE {
+                editor.record(drag.before);
+                let shape = Collider::Circle { radius: NEW_RADIUS };
+                spawn_obstacle(&mut commands, point, 0.0, shape).insert((FromLevel, Selected));
+            }
+        }
+        DragKind::Move { .. } | DragKind::Resize { .. } => {
+            let after = snapshot(obstacles.iter().map(|(_, obstacle, shape, ..)| (obstacle, shape)));
+            if after != drag.before {
+                editor.record(drag.before);
+            }
+        }
+    }
+}
+
+fn delete_selected(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut editor: ResMut<Editor>,
+    obstacles: Query<(Entity, &Obstacle, &Collider, Has<Selected>), With<FromLevel>>,
+) {
+    if !editor.enabled || !actions.just_pressed(action::EDITOR_DELETE) {
+        return;
+    }
+    let selected: Vec<Entity> = obstacles.iter().filter(|item| item.3).map(|item| item.0).collect();
+    if selected.is_empty() {
+        return;
+    }
+    editor.record(snapshot(obstacles.iter().map(|(_, obstacle, shape, _)| (obstacle, shape))));
+    editor.drag = None;
+    for entity in selected {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn undo_redo(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut editor: ResMut<Editor>,
+    old: Query<Entity, With<FromLevel>>,
+    obstacles: Query<(&Obstacle, &Collider), With<FromLevel>>,
+) {
+    if !editor.enabled || !actions.pressed(action::EDITOR_COMMAND) {
+        return;
+    }
+    let level = if actions.just_pressed(action::EDITOR_UNDO) {
+        editor.undo(snapshot(obstacles.iter()))
+    } else if actions.just_pressed(action::EDITOR_REDO) {
+        editor.redo(snapshot(obstacles.iter()))
+    } else {
+        return;
+    };
+    let Some(level) = level else { return; };
+    respawn_obstacles(&mut commands, &old, &level);
+    editor.drag = None;
+}
+
+fn save_and_load(
+    actions: Res<ActionState>,
+    mut editor: ResMut<Editor>,
+    current: Res<CurrentLevel>,
+    asset_server: Res<AssetServer>,
+    obstacles: Query<(&Obstacle, &Collider), With<FromLevel>>,
+    #[cfg(not(target_arch = "wasm32"))] mut watch: ResMut<blog_common::AssetWatch<Level>>,
+) {
+    if !editor.enabled || !actions.pressed(action::EDITOR_COMMAND) {
+        return;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if actions.just_pressed(action::EDITOR_SAVE) {
+        let level = Level {
+            player_spawn: current.player_spawn.unwrap_or_default(),
+            obstacles: snapshot(obstacles.iter()),
+        };
+        match level.save(LEVEL_PATH) {
+            Ok(()) => {
+                watch.saved(LEVEL_PATH);
+                info!("Saved the level to {}", Level::file(LEVEL_PATH).display());
+            }
+            Err(err) => error!("Could not save the level to {}: {err}", Level::file(LEVEL_PATH).display()),
+        }
+    }
+
+    // the file replaces what's on screen once it has loaded, `record_load` makes that undoable
+    if actions.just_pressed(action::EDITOR_LOAD) {
+        editor.loading = Some(snapshot(obstacles.iter()));
+        editor.drag = None;
+        asset_server.reload(LEVEL_PATH);
+    }
+}
+
+// Records a load for undo once the level has actually been swapped in. If the file didn't load, or is
+// the same as what's on screen, there's nothing to undo.
+fn record_load(
+    mut editor: ResMut<Editor>,
+    mut loaded: EventReader<AssetEvent<Level>>,
+    mut failed: EventReader<AssetLoadFailedEvent<Level>>,
+    current: Res<CurrentLevel>,
+    levels: Res<Assets<Level>>,
+) {
+    let id = current.handle.id();
+    if failed.read().any(|failure| failure.id == id) {
+        editor.loading = None;
+    }
+    if !loaded.read().any(|event| event.is_modified(id)) {
+        return;
+    }
+    let Some(before) = editor.loading.take() else { return; };
+    if levels.get(id).is_some_and(|level| level.obstacles != before) {
+        editor.record(before);
+    }
+}
+
+fn draw_handles(
+    mut gizmos: Gizmos,
+    editor: Res<Editor>,
+    busy: Res<EditorBusy>,
+    obstacles: Query<(Entity, &Obstacle, &Collider), (With<FromLevel>, With<Selected>)>,
+) {
+    if !editor.enabled {
+        return;
+    }
+    for (entity, obstacle, shape) in &obstacles {
+        let color = if busy.0 == Some(entity) { YELLOW } else { WHITE };
+        let handle = Isometry2d::from_translation(handle_position(obstacle, shape));
+        gizmos.rect_2d(handle, Vec2::splat(HANDLE_SIZE * 2.0), color);
+    }
+}
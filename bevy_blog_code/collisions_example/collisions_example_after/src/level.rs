@@ -0,0 +1,243 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    asset::{io::Reader, AssetLoadFailedEvent, AssetLoader, LoadContext},
+    prelude::*,
+};
+use blog_common::Collider;
+use serde::{Deserialize, Serialize};
+
+use crate::{spawn_obstacle, Player};
+
+// Levels are RON files in `assets/levels`. They say where the player starts and where each obstacle is,
+// loaded through `LevelLoader` like any other asset. Saving the file while the game runs swaps the level in.
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .add_systems(Startup, load_level)
+            .add_systems(Update, (spawn_level, report_level_errors));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(blog_common::AssetWatchPlugin::<Level>::default());
+    }
+}
+
+// The level the example starts with, relative to the `assets` folder
+pub const LEVEL_PATH: &str = "levels/first.level.ron";
+
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)] // a typo in a field name is an error instead of being quietly skipped
+pub struct Level {
+    pub player_spawn: Vec2,
+    #[serde(default)]
+    pub obstacles: Vec<LevelObstacle>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelObstacle {
+    pub position: Vec2,
+    #[serde(default)]
+    pub angle: f32, // in degrees, anticlockwise. Circles and `Aabb` boxes look the same at any angle.
+    pub shape: Collider,
+}
+
+impl Level {
+    // The shapes have to make sense for the collision code, a negative radius or a dented polygon would
+    // give odd results much later instead of an error now
+    pub fn check(&self) -> Result<(), LevelError> {
+        for (index, obstacle) in self.obstacles.iter().enumerate() {
+            check_shape(&obstacle.shape).map_err(|problem| {
+                LevelError::Invalid(format!("obstacle {} (at {}): {problem}", index + 1, obstacle.position))
+            })?;
+        }
+        Ok(())
+    }
+
+    // Where a level in the `assets` folder is on disk
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn file(path: &str) -> std::path::PathBuf {
+        bevy::asset::io::file::FileAssetReader::get_base_path().join("assets").join(path)
+    }
+
+    // Writes the level back in the same format it's read in. Comments in the old file are lost.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|err| err.to_string())?;
+        std::fs::write(Self::file(path), text).map_err(|err| err.to_string())
+    }
+}
+
+pub fn check_shape(shape: &Collider) -> Result<(), String> {
+    let positive = |name: &str, value: f32| {
+        if value > 0.0 && value.is_finite() {
+            Ok(())
+        } else {
+            Err(format!("{name} has to be more than 0, not {value}"))
+        }
+    };
+    match shape {
+        Collider::Circle { radius } => positive("radius", *radius),
+        Collider::Aabb { half_extents } | Collider::Obb { half_extents } => {
+            positive("the x of half_extents", half_extents.x)?;
+            positive("the y of half_extents", half_extents.y)
+        }
+        Collider::Capsule { half_length, radius } => {
+            if *half_length < 0.0 {
+                return Err(format!("half_length can't be negative, it's {half_length}"));
+            }
+            positive("radius", *radius)
+        }
+        Collider::Polygon { points } => {
+            if points.len() < 3 {
+                return Err(format!("a polygon needs at least 3 points, this one has {}", points.len()));
+            }
+            if let Some(point) = points.iter().find(|point| !point.is_finite()) {
+                return Err(format!("polygon points have to be numbers, not {point}"));
+            }
+            // the edges into and out of each corner
+            let corners: Vec<(Vec2, Vec2)> = (0..points.len())
+                .map(|i| {
+                    let [a, b, c] = [0, 1, 2].map(|offset| points[(i + offset) % points.len()]);
+                    (b - a, c - b)
+                })
+                .collect();
+            // every corner has to turn the same way
+            let (left, right) = corners.iter().fold((false, false), |(left, right), (into, out)| {
+                let turn = into.perp_dot(*out);
+                (left || turn > 0.0, right || turn < 0.0)
+            });
+            // and all of them together only once around, the points of a star all turn the same way too
+            let turned: f32 = corners
+                .iter()
+                .filter(|(into, out)| *into != Vec2::ZERO && *out != Vec2::ZERO)
+                .map(|(into, out)| into.angle_to(*out))
+                .sum();
+            if (left && right) || turned.abs() > TAU + 1e-3 {
+                return Err("the polygon has to be convex, with its points going around in order".to_string());
+            }
+            Ok(())
+        }
+    }
+}
+
+// Everything that can go wrong reading a level. Bevy logs it along with the file's name.
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl std::fmt::Display for LevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "could not read the file: {err}"),
+            LevelError::Parse(err) => {
+                write!(f, "line {}, column {}: {}", err.position.line, err.position.col, err.code)
+            }
+            LevelError::Invalid(problem) => write!(f, "{problem}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(err: std::io::Error) -> Self {
+        LevelError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for LevelError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        LevelError::Parse(err)
+    }
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _context: &mut LoadContext<'_>,
+    ) -> Result<Level, LevelError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level: Level = ron::de::from_bytes(&bytes)?;
+        level.check()?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+// The level being played, and where it put the player last time
+#[derive(Resource)]
+pub struct CurrentLevel {
+    pub handle: Handle<Level>,
+    pub player_spawn: Option<Vec2>,
+}
+
+// Marks the obstacles that came from the level file, so a reload knows which ones to replace
+#[derive(Component)]
+pub struct FromLevel;
+
+fn load_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CurrentLevel { handle: asset_server.load(LEVEL_PATH), player_spawn: None });
+}
+
+// Builds the level once it has loaded, and again every time it's reloaded
+fn spawn_level(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Level>>,
+    levels: Res<Assets<Level>>,
+    mut current: ResMut<CurrentLevel>,
+    old_obstacles: Query<Entity, With<FromLevel>>,
+    mut player_query: Query<(&mut Player, &mut Transform)>,
+) {
+    let id = current.handle.id();
+    let changed = events.read().any(|event| event.is_added(id) || event.is_modified(id));
+    let Some(level) = levels.get(id).filter(|_| changed) else { return; };
+
+    respawn_obstacles(&mut commands, &old_obstacles, &level.obstacles);
+
+    // only move the player when the start moved, so shifting obstacles around doesn't send it back every save
+    if current.player_spawn != Some(level.player_spawn) {
+        current.player_spawn = Some(level.player_spawn);
+        for (mut player, mut transform) in &mut player_query {
+            player.position = level.player_spawn;
+            transform.translation = player.position.extend(0.0);
+        }
+    }
+    info!("Level ready with {} obstacles", level.obstacles.len());
+}
+
+// Swaps the level's obstacles for new ones. The obstacles `--stress` added stay where they are.
+pub fn respawn_obstacles(commands: &mut Commands, old: &Query<Entity, With<FromLevel>>, obstacles: &[LevelObstacle]) {
+    for entity in old {
+        commands.entity(entity).despawn_recursive();
+    }
+    for obstacle in obstacles {
+        let angle = obstacle.angle.to_radians();
+        spawn_obstacle(commands, obstacle.position, angle, obstacle.shape.clone()).insert(FromLevel);
+    }
+}
+
+fn report_level_errors(mut failures: EventReader<AssetLoadFailedEvent<Level>>) {
+    for failure in failures.read() {
+        warn!("{} didn't load, keeping the level as it was. Fix the file and save it again to retry.", failure.path);
+    }
+}
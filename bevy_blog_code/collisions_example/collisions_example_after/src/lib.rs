@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy::color::palettes::basic::*;
+use blog_common::{Collider, CollisionEnded, CollisionStarted, Selectable};
+
+// The player, the obstacles and the level they're in. The game itself is in main.rs,
+// these are here so the tests can reach them too.
+pub mod editor;
+pub mod level;
+
+#[derive(Component)]
+pub struct Player {
+    pub position: Vec2,
+    pub color: Srgba,
+    pub touching: usize, // how many obstacles it's up against, counted from the collision events
+}
+
+#[derive(Component)]
+pub struct Obstacle {
+    pub position: Vec2,
+    pub angle: f32, // in radians
+    pub color: Srgba,
+}
+
+impl Obstacle {
+    pub fn isometry(&self) -> Isometry2d {
+        Isometry2d::new(self.position, Rot2::radians(self.angle))
+    }
+}
+
+pub fn spawn_obstacle<'a>(commands: &'a mut Commands, position: Vec2, angle: f32, shape: Collider) -> EntityCommands<'a> {
+    let selectable = selection_box(&shape);
+    commands.spawn(( //Spawn an Obstacle entity
+        Obstacle {
+            position,
+            angle,
+            color: BLUE,
+        },
+        shape,
+        Transform::from_translation(position.extend(0.0)).with_rotation(Quat::from_rotation_z(angle)),
+        selectable,
+    ))
+}
+
+// Big enough to hold the shape however it's offset from the middle
+pub fn selection_box(shape: &Collider) -> Selectable {
+    let local = shape.bounds(Isometry2d::IDENTITY);
+    let half_extents = local.min.abs().max(local.max.abs());
+    Selectable { half_extents: half_extents.extend(0.0) }
+}
+
+// Green while the player is up against something, red otherwise
+pub fn player_color(
+    mut started: EventReader<CollisionStarted>,
+    mut ended: EventReader<CollisionEnded>,
+    mut player_query: Query<(Entity, &mut Player)>,
+) {
+    for event in started.read() {
+        for (entity, mut player) in &mut player_query {
+            if event.a == entity || event.b == entity {
+                player.touching += 1;
+            }
+        }
+    }
+    for event in ended.read() {
+        for (entity, mut player) in &mut player_query {
+            if event.a == entity || event.b == entity {
+                player.touching = player.touching.saturating_sub(1);
+            }
+        }
+    }
+    for (_, mut player) in &mut player_query {
+        player.color = if player.touching > 0 { GREEN } else { RED };
+    }
+}
@@ -1,91 +1,124 @@
 use bevy::prelude::*; 
 use bevy::color::palettes::basic::*;
-
-#[derive(Component)]
-struct Player {
-    position: Vec2,
-    color: Srgba,
-    size_radius: f32,
-} 
-#[derive(Component)]
-struct Obstacle {
-    position: Vec2,
-    color: Srgba,
-    size_radius: f32,
-} 
+use blog_common::{
+    action, context, move_and_slide, ActionState, Collider, CollisionPlugin, Collisions,
+    EnableInputContext, FollowPlugin, FollowTarget, InputMapPlugin, Random, Selectable, SelectionPlugin, SpatialHash,
+};
+use collisions_example_after::{editor::EditorPlugin, level::LevelPlugin, player_color, spawn_obstacle, Obstacle, Player};
 
 fn main() {     
     App::new()     
         .add_plugins(DefaultPlugins) 
+        .add_plugins((InputMapPlugin, SelectionPlugin, CollisionPlugin, FollowPlugin, LevelPlugin, EditorPlugin))
+        .enable_input_context(context::PLAYER)
         .add_systems(Startup, setup) // Startup runs once at the beginning
-        .add_systems(Update, draw_player)  // Update runs every frame
+        .add_systems(Update, (move_player, player_color, draw_shapes).chain())  // Update runs every frame
         .run();// Runs the application
 }
 
+// How many obstacles `--stress` scatters around
+const STRESS_OBSTACLES: usize = 10_000;
+
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default()); //Spawn a 2D camera entity
+    let player = commands.spawn(( //Spawn a Player entity
+        Player {
+            position: Vec2::new(0.0, 0.0),
+            color: RED,
+            touching: 0,
+        },
+        Collider::Circle { radius: 20.0 },
+        Transform::default(), // Kept in sync with position, for selecting
+        Selectable { half_extents: Vec3::new(20.0, 20.0, 0.0) },
+    )).id();
 
-    commands.spawn(Player { //Spawn a Player entity
-        position: Vec2::new(0.0, 0.0),
-        color: RED,
-        size_radius: 20.0,
-    });
+    // The obstacles and where the player starts come from assets/levels/first.level.ron, see level.rs
 
-    commands.spawn(Obstacle { //Spawn an Obstacle entity
-        position: Vec2::new(100.0, 100.0),
-        color: BLUE,
-        size_radius: 50.0,
-    });
+    // `cargo run -- --stress` fills a big area with obstacles, to check the game keeps up with lots of them.
+    // The camera follows the player around it.
+    if std::env::args().any(|arg| arg == "--stress") {
+        let mut random = Random(12345);
+        for _ in 0..STRESS_OBSTACLES {
+            let position = random.point(5000.0);
+            if position.length() > 300.0 { // keep some room around the start
+                spawn_obstacle(&mut commands, position, 0.0, Collider::Circle { radius: random.range(5.0, 20.0) });
+            }
+        }
+        commands.spawn((Camera2d, FollowTarget { dead_zone: Vec2::new(150.0, 100.0), ..FollowTarget::new(player) }));
+    } else {
+        commands.spawn(Camera2d); //Spawn a 2D camera entity
+    }
 }
 
-fn draw_player(
-    mut gizmos: Gizmos,
-    mut player_query: Query<&mut Player>, 
-    obstacle_query: Query<&Obstacle>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+// Works out where the player wants to go once per frame, then moves it there against every obstacle.
+// If any of them is in the way the player slides along it, and the obstacles it bumped into are reported
+// so they send collision events even though the shapes never overlap.
+fn move_player(
+    mut player_query: Query<(Entity, &mut Player, &Collider, &mut Transform), Without<Obstacle>>,
+    obstacle_query: Query<(Entity, &Obstacle, &Collider)>,
+    actions: Res<ActionState>,
+    spatial_hash: Res<SpatialHash>,
+    mut collisions: ResMut<Collisions>,
 ) {
-    let step = 5.0;
+    let mut step = 5.0;
+    if actions.pressed(action::PLAYER_DASH) {
+        step *= 20.0; // fast enough to jump past small obstacles in one frame, if the moves weren't swept
+    }
 
-    for mut player in &mut player_query {
-        for obstacle in &obstacle_query {
-            gizmos.circle_2d(player.position, player.size_radius, player.color); // Draw player
-            gizmos.circle_2d(obstacle.position, obstacle.size_radius, obstacle.color); // Draw obstacle
-            
-            let mut new_position = player.position;
+    for (player_entity, mut player, player_shape, mut transform) in &mut player_query {
+        let mut new_position = player.position;
 
-            if keyboard_input.pressed(KeyCode::ArrowLeft) {
-                new_position.x -= step; // Move left
-            } 
-            if keyboard_input.pressed(KeyCode::ArrowRight) {
-                new_position.x += step; // Move right
-            }
-            if keyboard_input.pressed(KeyCode::ArrowUp) {
-                new_position.y += step; // Move up
-            }
-            if keyboard_input.pressed(KeyCode::ArrowDown) {
-                new_position.y -= step; // Move down
-            }
+        if actions.pressed(action::PLAYER_LEFT) {
+            new_position.x -= step; // Move left
+        } 
+        if actions.pressed(action::PLAYER_RIGHT) {
+            new_position.x += step; // Move right
+        }
+        if actions.pressed(action::PLAYER_UP) {
+            new_position.y += step; // Move up
+        }
+        if actions.pressed(action::PLAYER_DOWN) {
+            new_position.y -= step; // Move down
+        }
+        new_position += actions.left_stick() * step; // Gamepad left stick, a half push moves half as far
 
-            if !check_collisions(new_position, &mut player, &obstacle) {
-                player.position = new_position; // Update if no collision
-            }
-        }    
+        // only the obstacles near the way from here to there can be in the way
+        let start = Isometry2d::from_translation(player.position);
+        let end = Isometry2d::from_translation(new_position);
+        let reach = player_shape.bounds(start).union(player_shape.bounds(end));
+        let obstacles: Vec<_> = spatial_hash
+            .query(reach)
+            .into_iter()
+            .filter_map(|entity| obstacle_query.get(entity).ok())
+            .map(|(entity, obstacle, shape)| (entity, shape, obstacle.isometry()))
+            .collect();
+
+        // slide along whatever is in the way instead of stopping dead
+        let slide = move_and_slide(player_shape, start, new_position - player.position, &obstacles);
+        for (obstacle_entity, contact) in slide.hits {
+            collisions.report(player_entity, obstacle_entity, contact);
+        }
+        player.position = slide.position;
+        transform.translation = player.position.extend(0.0);
     }
 }
 
-fn check_collisions(
-    new_position: Vec2, 
-    player: &mut Player,
-    obstacle: &Obstacle,
-) -> bool {
-    let distance = new_position.distance(obstacle.position);
-    let sum_radius = player.size_radius + obstacle.size_radius;
-
-    if distance < sum_radius { // if distance smaller than sum of radii
-        player.color = GREEN;
-        return true;
+// Only the obstacles the camera can see are drawn, there can be thousands of them
+fn draw_shapes(
+    mut gizmos: Gizmos,
+    camera: Single<(&GlobalTransform, &OrthographicProjection), With<Camera2d>>,
+    player_query: Query<(&Player, &Collider)>,
+    obstacle_query: Query<(&Obstacle, &Collider)>,
+    spatial_hash: Res<SpatialHash>,
+) {
+    for (player, shape) in &player_query {
+        shape.draw(&mut gizmos, Isometry2d::from_translation(player.position), player.color); // Draw player
     }
-    else {
-        return false;
+
+    let (camera_transform, projection) = *camera;
+    let center = camera_transform.translation().truncate();
+    let view = Rect::from_corners(center + projection.area.min, center + projection.area.max);
+    for entity in spatial_hash.query(view) {
+        let Ok((obstacle, shape)) = obstacle_query.get(entity) else { continue; };
+        shape.draw(&mut gizmos, obstacle.isometry(), obstacle.color); // Draw obstacle
     }
-}
\ No newline at end of file
+}
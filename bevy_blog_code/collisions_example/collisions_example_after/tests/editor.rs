@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use blog_common::Collider;
+use collisions_example_after::{editor::Editor, level::LevelObstacle};
+
+// A level with `count` circles in a row, so each one is different from the others
+fn level(count: usize) -> Vec<LevelObstacle> {
+    (0..count)
+        .map(|i| LevelObstacle {
+            position: Vec2::new(i as f32 * 100.0, 0.0),
+            angle: 0.0,
+            shape: Collider::Circle { radius: 10.0 },
+        })
+        .collect()
+}
+
+#[test]
+fn nothing_to_undo_at_first() {
+    let mut editor = Editor::default();
+    assert!(!editor.can_undo() && !editor.can_redo());
+    assert_eq!(editor.undo(level(0)), None);
+    assert_eq!(editor.redo(level(0)), None);
+    // trying doesn't leave anything behind
+    assert!(!editor.can_undo() && !editor.can_redo());
+}
+
+#[test]
+fn undo_goes_back_one_edit_at_a_time() {
+    let mut editor = Editor::default();
+    // three edits, each adding an obstacle
+    editor.record(level(0));
+    editor.record(level(1));
+    editor.record(level(2));
+    assert!(editor.can_undo() && !editor.can_redo());
+
+    assert_eq!(editor.undo(level(3)), Some(level(2)));
+    assert_eq!(editor.undo(level(2)), Some(level(1)));
+    assert_eq!(editor.undo(level(1)), Some(level(0)));
+    assert_eq!(editor.undo(level(0)), None);
+}
+
+#[test]
+fn redo_goes_forward_again_in_order() {
+    let mut editor = Editor::default();
+    editor.record(level(0));
+    editor.record(level(1));
+    assert_eq!(editor.undo(level(2)), Some(level(1)));
+    assert_eq!(editor.undo(level(1)), Some(level(0)));
+
+    assert_eq!(editor.redo(level(0)), Some(level(1)));
+    assert_eq!(editor.redo(level(1)), Some(level(2)));
+    assert_eq!(editor.redo(level(2)), None);
+    // and it can all be undone again
+    assert_eq!(editor.undo(level(2)), Some(level(1)));
+}
+
+#[test]
+fn a_new_edit_clears_redo() {
+    let mut editor = Editor::default();
+    editor.record(level(0));
+    editor.record(level(1));
+    assert_eq!(editor.undo(level(2)), Some(level(1)));
+    assert!(editor.can_redo());
+
+    // something else is changed instead of redoing
+    editor.record(level(1));
+    assert!(!editor.can_redo());
+    assert_eq!(editor.redo(level(3)), None);
+    assert_eq!(editor.undo(level(3)), Some(level(1)));
+    assert_eq!(editor.undo(level(1)), Some(level(0)));
+}
+
+#[test]
+fn only_the_latest_edits_are_kept() {
+    let mut editor = Editor::default();
+    for count in 0..150 {
+        editor.record(level(count));
+    }
+    let mut undone = Vec::new();
+    while let Some(level) = editor.undo(Vec::new()) {
+        undone.push(level.len());
+    }
+    // the oldest 50 were dropped
+    assert_eq!(undone, (50..150).rev().collect::<Vec<_>>());
+}
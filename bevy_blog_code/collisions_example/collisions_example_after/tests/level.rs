@@ -0,0 +1,116 @@
+use std::f32::consts::PI;
+
+use bevy::{asset::LoadState, prelude::*};
+use blog_common::{test_support::load_asset, Collider};
+use collisions_example_after::level::{check_shape, Level, LevelPlugin};
+
+// Loads one of the levels in tests/levels through the asset server, the way the game does
+fn load(file: &str) -> (App, Handle<Level>, LoadState) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin { file_path: "tests/levels".to_string(), ..default() }))
+        .add_plugins(LevelPlugin);
+    let (handle, state) = load_asset(&mut app, file);
+    (app, handle, state)
+}
+
+fn load_error(file: &str) -> String {
+    match load(file).2 {
+        LoadState::Failed(err) => err.to_string(),
+        state => panic!("{file} should not load, it's {state:?}"),
+    }
+}
+
+#[test]
+fn a_good_level_loads() {
+    let (app, handle, state) = load("good.level.ron");
+    assert!(matches!(state, LoadState::Loaded), "{state:?}");
+    let level = app.world().resource::<Assets<Level>>().get(&handle).unwrap();
+    assert_eq!(level.player_spawn, Vec2::new(10.0, -20.0));
+    assert_eq!(level.obstacles.len(), 2);
+    assert_eq!(level.obstacles[0].angle, 0.0);
+    assert_eq!(level.obstacles[1].shape, Collider::Obb { half_extents: Vec2::new(60.0, 15.0) });
+}
+
+#[test]
+fn malformed_ron_says_where() {
+    // a missing comma after the position
+    let err = load_error("malformed.level.ron");
+    assert!(err.contains("line 4"), "{err}");
+}
+
+#[test]
+fn misspelled_fields_are_errors() {
+    let err = load_error("misspelled.level.ron");
+    assert!(err.contains("colour"), "{err}");
+}
+
+#[test]
+fn invalid_shapes_say_which_obstacle() {
+    let err = load_error("invalid.level.ron");
+    assert!(err.contains("obstacle 2 (at [-50, 0])"), "{err}");
+    assert!(err.contains("radius has to be more than 0, not -5"), "{err}");
+}
+
+#[test]
+fn shapes_that_make_sense_pass() {
+    let shapes = [
+        Collider::Circle { radius: 1.0 },
+        Collider::Aabb { half_extents: Vec2::new(1.0, 2.0) },
+        Collider::Obb { half_extents: Vec2::new(1.0, 2.0) },
+        Collider::Capsule { half_length: 0.0, radius: 1.0 }, // just a circle
+        // either way around
+        Collider::Polygon { points: vec![Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(0.0, 1.0)] },
+        Collider::Polygon { points: vec![Vec2::new(0.0, 1.0), Vec2::new(1.0, -1.0), Vec2::new(-1.0, -1.0)] },
+        // a point repeated, or one on an edge, doesn't change the shape
+        Collider::Polygon {
+            points: vec![
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(0.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(0.0, 1.0),
+            ],
+        },
+    ];
+    for shape in shapes {
+        assert_eq!(check_shape(&shape), Ok(()), "{shape:?}");
+    }
+}
+
+#[test]
+fn shapes_that_do_not_make_sense_fail() {
+    let shapes = [
+        (Collider::Circle { radius: 0.0 }, "radius has to be more than 0"),
+        (Collider::Circle { radius: f32::NAN }, "radius has to be more than 0"),
+        (Collider::Circle { radius: f32::INFINITY }, "radius has to be more than 0"),
+        (Collider::Aabb { half_extents: Vec2::new(1.0, -1.0) }, "the y of half_extents"),
+        (Collider::Obb { half_extents: Vec2::new(0.0, 1.0) }, "the x of half_extents"),
+        (Collider::Capsule { half_length: -1.0, radius: 1.0 }, "half_length can't be negative"),
+        (Collider::Capsule { half_length: 1.0, radius: 0.0 }, "radius has to be more than 0"),
+        (Collider::Polygon { points: vec![Vec2::ZERO, Vec2::X] }, "at least 3 points"),
+        (
+            // an arrowhead, dented on one side
+            Collider::Polygon {
+                points: vec![Vec2::new(-1.0, -1.0), Vec2::new(0.0, 0.0), Vec2::new(1.0, -1.0), Vec2::new(0.0, 1.0)],
+            },
+            "convex",
+        ),
+        (
+            // a five pointed star, every corner turns left but it goes around twice
+            Collider::Polygon { points: (0..5).map(|i| Vec2::from_angle(i as f32 * 4.0 * PI / 5.0)).collect() },
+            "convex",
+        ),
+        (
+            Collider::Polygon { points: vec![Vec2::ZERO, Vec2::X, Vec2::new(f32::NAN, 1.0)] },
+            "polygon points have to be numbers",
+        ),
+        (
+            Collider::Polygon { points: vec![Vec2::ZERO, Vec2::X, Vec2::new(0.0, f32::INFINITY)] },
+            "polygon points have to be numbers",
+        ),
+    ];
+    for (shape, problem) in shapes {
+        let err = check_shape(&shape).unwrap_err();
+        assert!(err.contains(problem), "{shape:?} gave {err}");
+    }
+}
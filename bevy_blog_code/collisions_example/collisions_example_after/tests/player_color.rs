@@ -0,0 +1,56 @@
+use bevy::{color::palettes::basic::*, prelude::*};
+use blog_common::{Collider, CollisionPlugin};
+use collisions_example_after::{player_color, Player};
+
+// The player at the origin between two obstacles it overlaps, with only the collision events driving its colour
+fn app() -> (App, Entity, [Entity; 2]) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, CollisionPlugin)).add_systems(Update, player_color);
+    let player = Player { position: Vec2::ZERO, color: RED, touching: 0 };
+    let player = app.world_mut().spawn((player, Collider::Circle { radius: 20.0 }, Transform::default())).id();
+    let mut obstacle = |x: f32| app.world_mut().spawn((Collider::Circle { radius: 10.0 }, Transform::from_xyz(x, 0.0, 0.0))).id();
+    let obstacles = [obstacle(25.0), obstacle(-25.0)];
+    (app, player, obstacles)
+}
+
+// The events are sent after Update, so it takes a second frame for the player to see them
+fn two_frames(app: &mut App) {
+    app.update();
+    app.update();
+}
+
+fn player(app: &App, entity: Entity) -> (usize, Srgba) {
+    let player = app.world().get::<Player>(entity).unwrap();
+    (player.touching, player.color)
+}
+
+#[test]
+fn touching_counts_every_obstacle() {
+    let (mut app, entity, [left, _]) = app();
+    two_frames(&mut app);
+    assert_eq!(player(&app, entity), (2, GREEN));
+
+    // still green while one of them is left
+    app.world_mut().despawn(left);
+    two_frames(&mut app);
+    assert_eq!(player(&app, entity), (1, GREEN));
+
+    app.world_mut().get_mut::<Transform>(entity).unwrap().translation.y = 100.0;
+    two_frames(&mut app);
+    assert_eq!(player(&app, entity), (0, RED));
+
+    // and back again
+    app.world_mut().get_mut::<Transform>(entity).unwrap().translation.y = 0.0;
+    two_frames(&mut app);
+    assert_eq!(player(&app, entity), (1, GREEN));
+}
+
+#[test]
+fn standing_still_stays_green() {
+    let (mut app, entity, _) = app();
+    two_frames(&mut app);
+    for _ in 0..5 {
+        app.update();
+        assert_eq!(player(&app, entity), (2, GREEN));
+    }
+}
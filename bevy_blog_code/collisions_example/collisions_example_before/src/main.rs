@@ -1,5 +1,6 @@
 use bevy::prelude::*; 
 use bevy::color::palettes::basic::*;
+use blog_common::{action, context, ActionState, EnableInputContext, InputMapPlugin};
 
 #[derive(Component)]
 struct Player {
@@ -11,13 +12,15 @@ struct Player {
 fn main() {     
     App::new()     
         .add_plugins(DefaultPlugins) 
+        .add_plugins(InputMapPlugin)
+        .enable_input_context(context::PLAYER)
         .add_systems(Startup, setup) // Startup runs once at the beginning
         .add_systems(Update, draw_player)  // Update runs every frame
         .run();// Runs the application
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default()); //Spawn a 2D camera entity
+    commands.spawn(Camera2d); //Spawn a 2D camera entity
 
     commands.spawn(Player { //Spawn a Player entity
         position: Vec2::new(0.0, 0.0),
@@ -29,24 +32,25 @@ fn setup(mut commands: Commands) {
 fn draw_player(
     mut gizmos: Gizmos,
     mut player_query: Query<&mut Player>, 
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
 ) {
     let step = 5.0;
 
     for mut player in &mut player_query {
         gizmos.circle_2d(player.position, player.size_radius, player.color); // Draw player
         
-        if keyboard_input.pressed(KeyCode::ArrowLeft) {
+        if actions.pressed(action::PLAYER_LEFT) {
             player.position.x -= step; // Move left
         } 
-        if keyboard_input.pressed(KeyCode::ArrowRight) {
+        if actions.pressed(action::PLAYER_RIGHT) {
             player.position.x += step; // Move right
         }
-        if keyboard_input.pressed(KeyCode::ArrowUp) {
+        if actions.pressed(action::PLAYER_UP) {
             player.position.y += step; // Move up
         }
-        if keyboard_input.pressed(KeyCode::ArrowDown) {
+        if actions.pressed(action::PLAYER_DOWN) {
             player.position.y -= step; // Move down
         }
+        player.position += actions.left_stick() * step; // Gamepad left stick, a half push moves half as far
     }    
 }
\ No newline at end of file
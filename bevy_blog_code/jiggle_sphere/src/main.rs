@@ -1,23 +1,9 @@
-use bevy::{
-    prelude::*,
-    color::palettes::css::*, 
-    window::{PrimaryWindow, Window},
-    input::mouse::MouseButton,
+use bevy::prelude::*;
+use blog_common::{
+    action, context, ActionState, DragPlugin, EnableInputContext, Draggable, FlyCamera, FlyCameraPlugin, Grid, GridPlugin,
+    InputMapPlugin, PickEvent, PickShape, Pickable, PickingPlugin,
 };
 
-#[derive(Component)]
-pub struct Grid {
-    enabled: bool,
-    size: i32,
-    cell_size: f32,
-}
-
-#[derive(Component)]
-struct FlyCamera {
-    yaw: f32,   // rotation around Y axis in radians
-    pitch: f32, // rotation around X axis in radians
-}
-
 #[derive(Component)]
 struct SphereTag;
 
@@ -35,9 +21,9 @@ const JIGGLE_DURATION: f32 = 1.5; // seconds
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins((InputMapPlugin, GridPlugin, FlyCameraPlugin, PickingPlugin, DragPlugin))
+        .enable_input_context(context::JIGGLE)
         .add_systems(Startup, setup)
-        .add_systems(Update, grid)
-        .add_systems(Update, fly_camera)
         .add_systems(Update, jiggle_sphere)
         .add_systems(Update, jiggle_on_click) // <-- Add this
         .run();
@@ -53,7 +39,7 @@ fn setup(
         Name::new("MainCamera"),
         Camera3d::default(),
         Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-        FlyCamera { yaw: 0.0, pitch: 0.0 },
+        FlyCamera::default(),
         MainCamera,
     ));
 
@@ -67,11 +53,7 @@ fn setup(
     ));
 
     // grid entity
-    commands.spawn(Grid {
-        enabled: false,
-        size: 10,
-        cell_size: 1.0
-    });
+    commands.spawn(Grid::default());
 
     // sphere
     commands.spawn((
@@ -84,103 +66,17 @@ fn setup(
         Transform::from_xyz(0.0, 0.0, 0.0),
         SphereTag,
         JiggleAnimation::default(),
+        Pickable {
+            shape: PickShape::Sphere { radius: 0.5 },
+        },
+        Draggable { snap: true },
     ));
 }
 
-// Draw grid and axes, toggle with Space
-fn grid(
-    mut gizmos: Gizmos,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut grid: Single<&mut Grid>,
-) {
-        // toggle grid visibility
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        grid.enabled = !grid.enabled;
-    }
-
-    if grid.enabled {
-        // grid lines
-        for i in -grid.size..=grid.size {
-            let pos = i as f32 * grid.cell_size;
-            gizmos.line(
-                Vec3::new(pos, 0.0, -grid.size as f32),
-                Vec3::new(pos, 0.0, grid.size as f32),
-                GREY,
-            );
-            gizmos.line(
-                Vec3::new(-grid.size as f32, 0.0, pos),
-                Vec3::new(grid.size as f32, 0.0, pos),
-                GREY,
-            );
-        }
-        // axes
-        gizmos.line(Vec3::new(-100.0, 0.01, 0.0), Vec3::new(100.0, 0.0, 0.0), RED);
-        gizmos.line(Vec3::new(0.0, -100.0, 0.0), Vec3::new(0.0, 100.0, 0.0), GREEN);
-        gizmos.line(Vec3::new(0.0, 0.01, -100.0), Vec3::new(0.0, 0.0, 100.0), BLUE);
-        }
-}
-
-// WASD + QE movement and arrow keys for camera rotation
-fn fly_camera(
-    mut query: Query<(&mut Transform, &mut FlyCamera)>,
-    keys: Res<ButtonInput<KeyCode>>,
-    time: Res<Time>,
-) {
-    let speed = 5.0;
-    let rot_speed = 1.5; // radians/sec
-
-    for (mut transform, mut camera) in &mut query {
-        // spin on Y axis
-        if keys.pressed(KeyCode::ArrowLeft) {
-            camera.yaw += rot_speed * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::ArrowRight) {
-            camera.yaw -= rot_speed * time.delta_secs();
-        }
-        // pitch up/down
-        if keys.pressed(KeyCode::ArrowUp) {
-            camera.pitch += rot_speed * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::ArrowDown) {
-            camera.pitch -= rot_speed * time.delta_secs();
-        }
-        camera.pitch = camera.pitch.clamp(-1.54, 1.54); // clamp pitch to avoid flipping
-
-        // apply yaw and pitch rotation to the camera
-        transform.rotation =
-            Quat::from_axis_angle(Vec3::Y, camera.yaw) *
-            Quat::from_axis_angle(Vec3::X, camera.pitch);
-
-        // movement (WASD for horizontal, QE for vertical)
-        let mut direction = Vec3::ZERO;
-        if keys.pressed(KeyCode::KeyW) {
-            direction += *transform.forward() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyS) {
-            direction -= *transform.forward() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyA) {
-            direction -= *transform.right() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyD) {
-            direction += *transform.right() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyQ) {
-            direction += Vec3::Y * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyE) {
-            direction -= Vec3::Y * time.delta_secs();
-        }
-        if direction.length_squared() > 0.0 {
-            transform.translation += direction.normalize() * speed * time.delta_secs();
-        }
-    }
-}
-
-// Procedural jiggle animation for the sphere when A is pressed
+// Procedural jiggle animation for the sphere when the jiggle action (B) is pressed
 fn jiggle_sphere(
     time: Res<Time>,
-    keys: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     mut query: Query<(&mut Transform, &mut JiggleAnimation), With<SphereTag>>,
 ) {
     let jiggle_amplitude = 1.0; // Start amplitude (big jiggle)
@@ -188,7 +84,7 @@ fn jiggle_sphere(
 
     for (mut transform, mut jiggle) in &mut query {
         // Start jiggle on B press (not while held)
-        if keys.just_pressed(KeyCode::KeyB) {
+        if actions.just_pressed(action::JIGGLE) {
             jiggle.active = true;
             jiggle.timer = 0.0;
         }
@@ -213,29 +109,13 @@ fn jiggle_sphere(
 }
 
 fn jiggle_on_click(
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
-    mut query: Query<(&GlobalTransform, &mut JiggleAnimation), With<SphereTag>>,
+    mut picks: EventReader<PickEvent>,
+    mut query: Query<&mut JiggleAnimation, With<SphereTag>>,
 ) {
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        let Ok(window) = windows.get_single() else { return; };
-        if let Some(cursor_pos) = window.cursor_position() {
-            let Ok((camera, camera_transform)) = cameras.get_single() else { return; };
-            if let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_pos) {
-                let ray_direction = ray.direction.as_vec3();
-                for (sphere_transform, mut jiggle) in &mut query {
-                    let center = sphere_transform.translation();
-                    let radius = 0.5;
-                    let origin_to_center = center - ray.origin;
-                    let tca = origin_to_center.dot(ray_direction);
-                    let d2 = origin_to_center.length_squared() - tca * tca;
-                    if d2 <= radius * radius {
-                        jiggle.active = true;
-                        jiggle.timer = 0.0;
-                    }
-                }
-            }
+    for pick in picks.read() {
+        if let Ok(mut jiggle) = query.get_mut(pick.entity) {
+            jiggle.active = true;
+            jiggle.timer = 0.0;
         }
     }
 }
\ No newline at end of file
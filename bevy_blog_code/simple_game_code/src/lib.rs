@@ -0,0 +1,97 @@
+use bevy::prelude::*; // includes commonly used types, traits, and functions from the Bevy game engine.
+use bevy::color::palettes::basic::*;
+use blog_common::{Collider, Tile, Tilemap};
+
+// How far the player goes in one second, and how fast it turns (in radians per second).
+// These used to be 3.0 and 0.1 per frame, which is the same at 60 frames a second.
+pub const PLAYER_SPEED: f32 = 180.0;
+pub const PLAYER_TURN_SPEED: f32 = 6.0;
+pub const PLAYER_RADIUS: f32 = 20.0;
+
+#[derive(Component)] // Marks the Player struct as a component that can be attached to entities in Bevy's Entity-Component-System.
+#[require(PlayerControls, Transform)]
+pub struct Player {
+    pub position: Vec2,
+    pub previous_position: Vec2, // Where it was one fixed step ago, to draw it smoothly in between.
+    pub spawn: Vec2, // Where it goes back to after touching a hazard.
+    pub direction_angle: f32,
+    pub speed: f32,
+    pub turn_speed: f32,
+    pub color: Srgba,
+}
+
+impl Player {
+    pub fn new(position: Vec2) -> Self {
+        Player {
+            position,
+            previous_position: position,
+            spawn: position,
+            direction_angle: 0.0,
+            speed: PLAYER_SPEED,
+            turn_speed: PLAYER_TURN_SPEED,
+            color: RED,
+        }
+    }
+}
+
+// What the player wants to do, each from -1 to 1. Keys and the gamepad fill this in,
+// and a test can set it directly without a window or a keyboard.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq)]
+pub struct PlayerControls {
+    pub turn: f32,   // Positive turns right.
+    pub thrust: f32, // Positive moves forward.
+}
+
+// Moves the player on a fixed timestep, so it goes the same way no matter how fast the game draws.
+// The drawn position glides between the last two steps.
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, move_player)
+            // After the fixed steps of this frame, so it sees the newest position.
+            .add_systems(RunFixedMainLoop, interpolate_player.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop));
+    }
+}
+
+// Runs a fixed number of times a second (64 by default), so the time step is always the same.
+// With a `Tilemap` around, the player slides along its walls and starts over after touching a hazard.
+fn move_player(time: Res<Time>, mut player_query: Query<(&mut Player, &PlayerControls)>, maps: Query<&Tilemap>) {
+    let delta = time.delta_secs();
+    let map = maps.get_single().ok();
+    let shape = Collider::Circle { radius: PLAYER_RADIUS };
+    for (mut player, controls) in &mut player_query {
+        player.previous_position = player.position;
+
+        let turn = controls.turn.clamp(-1.0, 1.0);
+        player.direction_angle += player.turn_speed * turn * delta;
+
+        // Calculate the movement vector based on the player's direction and speed.
+        let x = f32::sin(player.direction_angle);
+        let y = f32::cos(player.direction_angle);
+        let movement_vector = Vec2::new(x, y) * player.speed * delta;
+        let motion = movement_vector * controls.thrust.clamp(-1.0, 1.0);
+
+        let Some(map) = map else {
+            player.position += motion;
+            continue;
+        };
+        let slide = map.move_and_slide(&shape, Isometry2d::from_translation(player.position), motion);
+        player.position = slide.position;
+        if map.touches(Tile::Hazard, &shape, Isometry2d::from_translation(player.position)) {
+            // Jump straight back instead of gliding there.
+            player.position = player.spawn;
+            player.previous_position = player.spawn;
+        }
+    }
+}
+
+// The frame usually lands part way between two fixed steps. The transform is put that far
+// between the previous and the current position, so the player doesn't stutter.
+fn interpolate_player(fixed_time: Res<Time<Fixed>>, mut player_query: Query<(&Player, &mut Transform)>) {
+    let fraction = fixed_time.overstep_fraction();
+    for (player, mut transform) in &mut player_query {
+        let position = player.previous_position.lerp(player.position, fraction);
+        transform.translation = position.extend(0.0);
+    }
+}
@@ -1,66 +1,142 @@
 use bevy::prelude::*; // includes commonly used types, traits, and functions from the Bevy game engine.
-use bevy::color::palettes::basic::*;
+use bevy::asset::AssetLoadFailedEvent;
+use blog_common::{
+    action, ActionState, EnableInputContext, FollowPlugin, FollowTarget, Grid, GridPlane, GridPlugin, InputMapPlugin, Tilemap, TilemapPlugin,
+};
+use simple_game_code::{Player, PlayerControls, PlayerPlugin, PLAYER_RADIUS};
 //use bevy::input::ButtonInput;
 
-#[derive(Component)] // Marks the Player struct as a component that can be attached to entities in Bevy's Entity-Component-System.
-struct Player {
-    position: Vec2,
-    direction_angle: f32,
-    speed: f32,
-    color: Srgba,
-} 
 fn main() {     
     App::new() // Creates a new Bevy application.
     
         .add_plugins(DefaultPlugins) 
+        .add_plugins((InputMapPlugin, GridPlugin, FollowPlugin, PlayerPlugin, TilemapPlugin))
+        // The player has no dash here, which leaves Space for toggling the grid
+        .enable_input_context(&[action::PLAYER_LEFT, action::PLAYER_RIGHT, action::PLAYER_UP, action::PLAYER_DOWN])
 
         // Adds the setup system to the Startup stage, which runs once at the beginning.
         .add_systems(Startup, setup) 
 
-        //Adds the player_update system to the Update stage, which runs every frame.
+        // Reads the keys once a frame, before the fixed steps that move the player.
+        .add_systems(RunFixedMainLoop, read_controls.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop))
+
+        //Adds the draw_player system to the Update stage, which runs every frame.
         .add_systems(Update, draw_player) 
 
+        // Puts the map in the world once it has loaded, and again every time the file is saved.
+        .add_systems(Update, (place_map, report_map_errors))
+
          // Runs the application.
         .run();
 }
 
-fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default()); //Spawns a 2D camera entity.
+// The world, one character per tile. Edit it to change the level: # is a wall, ^ a hazard and @ the start.
+// Saving it while the game runs swaps the new map in.
+const MAP_PATH: &str = "maps/world.map.txt";
+const TILE_SIZE: f32 = 50.0; // the same as the grid, so the tiles line up with it
+
+// The map file, loaded like any other asset. `place_map` puts a copy of it in the world.
+#[derive(Resource)]
+struct CurrentMap {
+    handle: Handle<Tilemap>,
+    start: Option<Vec2>, // where the map put the player last time
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CurrentMap { handle: asset_server.load(MAP_PATH), start: None });
 
-    commands.spawn(Player { //Spawns a Player entity with these parameters.
-        position: Vec2::new(0.0, 0.0),
-        direction_angle: 0.0,
-        speed: 3.0,
-        color: RED,
+    let player = commands.spawn(
+        Player::new(Vec2::ZERO), //Spawns a Player entity, place_map moves it to where the map says it starts.
+    ).id();
+
+    commands.spawn(( //Spawns a 2D camera entity that follows the player once it gets near the edge of a box.
+        Camera2d,
+        FollowTarget {
+            dead_zone: Vec2::new(150.0, 100.0),
+            ..FollowTarget::new(player)
+        },
+    ));
+
+    // A background grid, otherwise there is nothing to show that the camera moves.
+    commands.spawn(Grid {
+        enabled: true,
+        plane: GridPlane::XY,
+        size: 40,
+        cell_size: 50.0,
+        fade_distance: 800.0,
+        ..default()
     });
 }
 
-fn draw_player(
-    mut gizmos: Gizmos,
-    mut player_query: Query<&mut Player>, 
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+// Puts the map in the world with its middle on the middle of the screen, once it has loaded
+// and again every time it's reloaded
+fn place_map(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Tilemap>>,
+    maps: Res<Assets<Tilemap>>,
+    mut current: ResMut<CurrentMap>,
+    mut placed: Query<&mut Tilemap>,
+    mut player_query: Query<&mut Player>,
+    mut camera_query: Query<&mut FollowTarget>,
 ) {
-    let size_radius = 20.0;
-    for mut player in &mut player_query {
-        gizmos.circle_2d(player.position, size_radius, player.color); // Draws a circle at the player's position.
-    
-        if keyboard_input.pressed(KeyCode::ArrowLeft) {
-            player.direction_angle -= 0.1; // Rotates the player to the left.
-        } 
-        if keyboard_input.pressed(KeyCode::ArrowRight) {
-            player.direction_angle += 0.1; // Rotates the player to the right.
+    let id = current.handle.id();
+    let changed = events.read().any(|event| event.is_added(id) || event.is_modified(id));
+    let Some(loaded) = maps.get(id).filter(|_| changed) else { return; };
+
+    let mut map = loaded.clone();
+    map.tile_size = TILE_SIZE;
+    map.origin = -map.bounds().size() / 2.0;
+    let start = map.spawn.map(|tile| map.tile_center(tile.as_ivec2())).unwrap_or(Vec2::ZERO);
+    for mut follow in &mut camera_query {
+        follow.bounds = Some(map.bounds()); // Never shows past the edge of the map.
+    }
+
+    // Only moves the player when the start moved, so changing walls doesn't send it back every save.
+    if current.start != Some(start) {
+        current.start = Some(start);
+        for mut player in &mut player_query {
+            *player = Player { direction_angle: player.direction_angle, ..Player::new(start) };
         }
+    }
 
-        // Calculate the movement vector based on the player's direction and speed.
-        let x = f32::sin(player.direction_angle);
-        let y = f32::cos(player.direction_angle);
-        let movement_vector = Vec2::new(x, y) * player.speed;
+    match placed.get_single_mut() {
+        Ok(mut old) => *old = map,
+        Err(_) => {
+            commands.spawn(map);
+        }
+    }
+}
+
+fn report_map_errors(mut failures: EventReader<AssetLoadFailedEvent<Tilemap>>) {
+    for failure in failures.read() {
+        warn!("{} didn't load, playing on without it. Fix the file and save it again to retry.", failure.path);
+    }
+}
 
-        if keyboard_input.pressed(KeyCode::ArrowUp) {
-            player.position += movement_vector; // Moves the player forward.
+fn read_controls(mut player_query: Query<&mut PlayerControls>, actions: Res<ActionState>) {
+    for mut controls in &mut player_query {
+        let stick = actions.left_stick(); // Gamepad left stick: sideways turns, forward and back moves.
+        let mut turn = stick.x;
+        let mut thrust = stick.y; // Half-pushed stick moves at half speed.
+        if actions.pressed(action::PLAYER_LEFT) {
+            turn -= 1.0; // Rotates the player to the left.
+        } 
+        if actions.pressed(action::PLAYER_RIGHT) {
+            turn += 1.0; // Rotates the player to the right.
+        }
+        if actions.pressed(action::PLAYER_UP) {
+            thrust += 1.0; // Moves the player forward.
         }
-        if keyboard_input.pressed(KeyCode::ArrowDown) {
-            player.position -= movement_vector; // Moves the player backward.
+        if actions.pressed(action::PLAYER_DOWN) {
+            thrust -= 1.0; // Moves the player backward.
         }
+        *controls = PlayerControls { turn, thrust };
+    }
+}
+
+fn draw_player(mut gizmos: Gizmos, player_query: Query<(&Player, &Transform)>) {
+    for (player, transform) in &player_query {
+        // Draws a circle where the player is drawn this frame, between its last two fixed steps.
+        gizmos.circle_2d(transform.translation.truncate(), PLAYER_RADIUS, player.color);
     }    
-}
\ No newline at end of file
+}
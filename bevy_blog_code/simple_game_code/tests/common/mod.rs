@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use simple_game_code::{Player, PlayerControls, PlayerPlugin};
+
+// A game with no window, where every frame takes exactly 1 / frame_rate seconds, with the player at `start`
+pub fn game(frame_rate: f64, start: Vec2, controls: PlayerControls) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, PlayerPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / frame_rate)));
+    app.world_mut().spawn((Player::new(start), controls));
+    app
+}
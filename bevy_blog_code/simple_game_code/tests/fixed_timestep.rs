@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use simple_game_code::{Player, PlayerControls, PLAYER_SPEED};
+
+mod common;
+
+// Where the player was after every fixed step
+#[derive(Resource, Default)]
+struct Trajectory(Vec<Vec2>);
+
+fn record(mut trajectory: ResMut<Trajectory>, player_query: Query<&Player>) {
+    trajectory.0.extend(player_query.iter().map(|player| player.position));
+}
+
+// A game that keeps the `Trajectory`, starting from the middle
+fn game(frame_rate: f64, controls: PlayerControls) -> App {
+    let mut app = common::game(frame_rate, Vec2::ZERO, controls);
+    app.init_resource::<Trajectory>().add_systems(FixedPostUpdate, record);
+    app
+}
+
+fn run(frame_rate: f64, seconds: f64, controls: PlayerControls) -> Vec<Vec2> {
+    let mut app = game(frame_rate, controls);
+    for _ in 0..(frame_rate * seconds).round() as usize {
+        app.update();
+    }
+    app.world_mut().remove_resource::<Trajectory>().unwrap().0
+}
+
+#[test]
+fn same_trajectory_at_any_frame_rate() {
+    // driving in a circle, so both turning and moving count
+    let controls = PlayerControls { turn: 0.5, thrust: 1.0 };
+    let trajectories = [30.0, 60.0, 144.0].map(|frame_rate| run(frame_rate, 3.0, controls));
+
+    // 3 seconds of 64 steps a second, less the first frame which has no time in it
+    for trajectory in &trajectories {
+        assert!((188..=192).contains(&trajectory.len()), "{} steps", trajectory.len());
+    }
+    let steps = trajectories.iter().map(Vec::len).min().unwrap();
+    for trajectory in &trajectories[1..] {
+        assert_eq!(trajectory[..steps], trajectories[0][..steps]);
+    }
+}
+
+#[test]
+fn speed_is_in_units_per_second() {
+    let controls = PlayerControls { turn: 0.0, thrust: 1.0 };
+    let trajectory = run(60.0, 1.0, controls);
+    let seconds = trajectory.len() as f32 / 64.0;
+    let moved = trajectory.last().unwrap().y;
+    assert!((moved - PLAYER_SPEED * seconds).abs() < 1e-2, "moved {moved} in {seconds} seconds");
+}
+
+#[test]
+fn drawn_between_the_last_two_steps() {
+    // frames faster than the fixed steps, so most of them land part way between two steps
+    let mut app = game(144.0, PlayerControls { turn: 0.0, thrust: 1.0 });
+    let mut in_between = 0;
+    for _ in 0..144 {
+        app.update();
+        let (player, transform) = app.world_mut().query::<(&Player, &Transform)>().single(app.world());
+        let drawn = transform.translation.y;
+        let (from, to) = (player.previous_position.y, player.position.y);
+        assert!(drawn >= from - 1e-3 && drawn <= to + 1e-3, "drawn at {drawn}, between {from} and {to}");
+        if drawn > from + 1e-3 && drawn < to - 1e-3 {
+            in_between += 1;
+        }
+    }
+    assert!(in_between > 72, "only {in_between} frames were drawn in between");
+}
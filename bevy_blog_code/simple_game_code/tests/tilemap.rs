@@ -0,0 +1,69 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::prelude::*;
+use blog_common::{Collider, Tile, Tilemap};
+use simple_game_code::{Player, PlayerControls, PLAYER_RADIUS};
+
+mod common;
+
+const TILE: f32 = 50.0;
+
+// The start on the left, a wall three tiles to the right of it and a hazard straight below it
+const MAP: &str = "\
+#######
+#@..#.#
+#.....#
+#^....#
+#######
+";
+
+// A game at 60 frames a second on the map, with the player on its start tile
+fn game(controls: PlayerControls) -> (App, Tilemap) {
+    let map = Tilemap::parse(MAP, TILE).unwrap();
+    let start = map.tile_center(map.spawn.unwrap().as_ivec2());
+    let mut app = common::game(60.0, start, controls);
+    app.world_mut().spawn(map.clone());
+    (app, map)
+}
+
+// 0 faces up, a quarter turn faces right
+fn turn_to(app: &mut App, angle: f32) {
+    app.world_mut().query::<&mut Player>().single_mut(app.world_mut()).direction_angle = angle;
+}
+
+fn player(app: &mut App) -> Vec2 {
+    app.world_mut().query::<&Player>().single(app.world()).position
+}
+
+#[test]
+fn walls_stop_the_player() {
+    // facing right, driving into the wall
+    let (mut app, map) = game(PlayerControls { turn: 0.0, thrust: 1.0 });
+    turn_to(&mut app, FRAC_PI_2);
+    for _ in 0..120 {
+        app.update();
+    }
+    let wall = map.tile_center(IVec2::new(4, 3)).x - TILE / 2.0;
+    let position = player(&mut app);
+    assert!(position.x <= wall - PLAYER_RADIUS && position.x > wall - PLAYER_RADIUS - 0.1, "ended at {position}");
+}
+
+#[test]
+fn hazards_send_the_player_back_to_the_start() {
+    // facing down, towards the hazard
+    let (mut app, map) = game(PlayerControls { turn: 0.0, thrust: 1.0 });
+    let start = player(&mut app);
+    turn_to(&mut app, PI);
+    let shape = Collider::Circle { radius: PLAYER_RADIUS };
+    let mut furthest = 0.0f32;
+    for _ in 0..60 {
+        app.update();
+        let position = player(&mut app);
+        furthest = furthest.max(start.distance(position));
+        let on_hazard = map.touches(Tile::Hazard, &shape, Isometry2d::from_translation(position));
+        assert!(!on_hazard, "standing on the hazard at {position}");
+    }
+    // it got near the hazard and was sent back at least once
+    assert!(furthest > TILE / 2.0, "only got {furthest} away");
+    assert!(player(&mut app).distance(start) < furthest);
+}
@@ -1,4 +1,5 @@
 use bevy::{color::palettes::basic::*, prelude::*};
+use blog_common::{action, context, ActionState, EnableInputContext, InputMapPlugin};
 
 #[derive(Component)]
 struct MyMusic;
@@ -13,6 +14,8 @@ struct ActiveNoteMi;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins(InputMapPlugin)
+        .enable_input_context(context::AUDIO)
         .add_systems(Startup, setup)
         .add_systems(Startup, set_initial_volume) 
         .add_systems(Update, button_system) // button stuff
@@ -214,18 +217,16 @@ fn button_system(
 }
 
 fn volume(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     music_controller: Query<&AudioSink, With<MyMusic>>,
 ) {
     if let Ok(sink) = music_controller.get_single() {
-        if keyboard_input.just_pressed(KeyCode::Equal) {
+        if actions.just_pressed(action::VOLUME_UP) {
             if sink.volume() < 4.9 {
                 sink.set_volume(sink.volume() + 0.1);
             }
-        } else if keyboard_input.just_pressed(KeyCode::Minus) {
-            if sink.volume() > 0.0 {
-                sink.set_volume(sink.volume() - 0.1);
-            }
+        } else if actions.just_pressed(action::VOLUME_DOWN) && sink.volume() > 0.0 {
+            sink.set_volume(sink.volume() - 0.1);
         }
     }
 
@@ -5,25 +5,16 @@ use bevy::{
     render::camera::Viewport, 
     window::{PrimaryWindow, Window}
 };
-
-#[derive(Component)]
-pub struct Grid {
-    enabled: bool,
-    size: i32,
-    cell_size: f32,
-}
+use blog_common::{
+    action, context, log_picks, ActionState, CameraBookmarksPlugin, EnableInputContext, FlyCamera, FlyCameraPlugin,
+    FollowPlugin, FollowTarget, Grid, GridPlugin, InputMapPlugin, PickShape, Pickable, PickingPlugin,
+};
 
 #[derive(Component)]
 struct FullScreen {
     enabled: bool,
 }
 
-#[derive(Component)]
-struct FlyCamera {
-    yaw: f32,   // rotation around Y axis in radians
-    pitch: f32, // pitch is rotation around X axis in radians
-}
-
 #[derive(Resource)]
 struct OrbitAngle(f32);
 #[derive(Resource)]
@@ -43,11 +34,13 @@ struct ElectronTrace {
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins((InputMapPlugin, GridPlugin, FlyCameraPlugin, CameraBookmarksPlugin, FollowPlugin))
+        .add_plugins(PickingPlugin)
+        .enable_input_context(context::WINDOW)
         .add_systems(Startup, setup)
-        .add_systems(Update, grid)
-        .add_systems(Update, fly_camera)
-        //.add_systems(Update, orbit_electron_system)
+        .add_systems(Update, orbit_electron_system)
         .add_systems(Update, setup_viewpoints)
+        .add_systems(Update, log_picks)
         .run();
 }
 
@@ -69,19 +62,19 @@ fn setup(
         .insert(BackgroundColor(BLACK.into()));
 
     // game view camera
-    commands.spawn((
+    let game_view = commands.spawn((
         Name::new("GameViewCamera"),
         Camera3d::default(),
         Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
         FullScreen { enabled: false },
-    ));
+    )).id();
 
     // main camera
     commands.spawn((
         Name::new("MainCamera"),
         Camera3d::default(),
         Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-        FlyCamera { yaw: 0.0, pitch: 0.0 },
+        FlyCamera::default(),
     ));
 
     // light source
@@ -94,11 +87,7 @@ fn setup(
     ));
 
     // grid entity
-    commands.spawn(Grid {
-        enabled: false,
-        size: 10,
-        cell_size: 1.0
-    });
+    commands.spawn(Grid::default());
 
     // core 
     commands.spawn((
@@ -111,10 +100,13 @@ fn setup(
         })),
         Transform::from_xyz(0.0, 0.0, 0.0),
         Core,
+        Pickable {
+            shape: PickShape::Sphere { radius: 0.5 },
+        },
     ));
 
     // electron
-    commands.spawn((
+    let electron = commands.spawn((
         Name::new("Electron"),
         Mesh3d(meshes.add(Sphere::new(0.2))),
         MeshMaterial3d(materials.add(StandardMaterial {
@@ -124,7 +116,18 @@ fn setup(
         })),
         Transform::from_xyz(2.0, 0.0, 0.0),
         Electron,
-    ));
+        Pickable {
+            shape: PickShape::Sphere { radius: 0.2 },
+        },
+    )).id();
+
+    // the game view camera keeps the electron in sight from a fixed distance
+    commands.entity(game_view).insert(FollowTarget {
+        offset: Vec3::new(3.0, 5.0, 5.0),
+        dead_zone: Vec2::new(0.5, 0.5),
+        look_at: true,
+        ..FollowTarget::new(electron)
+    });
 
     // Insert orbit/trace resources
     commands.insert_resource(OrbitAngle(0.0));
@@ -203,104 +206,12 @@ fn electron_trace_gizmo_system(
 }
 
 
-// Draw grid and axes, toggle with Space
-fn grid(
-    mut gizmos: Gizmos,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut grid: Single<&mut Grid>,
-) {
-        // toggle grid visibility
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        grid.enabled = !grid.enabled;
-    }
-
-    if grid.enabled {
-        // grid lines
-        for i in -grid.size..=grid.size {
-            let pos = i as f32 * grid.cell_size;
-            gizmos.line(
-                Vec3::new(pos, 0.0, -grid.size as f32),
-                Vec3::new(pos, 0.0, grid.size as f32),
-                GREY,
-            );
-            gizmos.line(
-                Vec3::new(-grid.size as f32, 0.0, pos),
-                Vec3::new(grid.size as f32, 0.0, pos),
-                GREY,
-            );
-        }
-        // axes
-        gizmos.line(Vec3::new(-100.0, 0.01, 0.0), Vec3::new(100.0, 0.0, 0.0), RED);
-        gizmos.line(Vec3::new(0.0, -100.0, 0.0), Vec3::new(0.0, 100.0, 0.0), GREEN);
-        gizmos.line(Vec3::new(0.0, 0.01, -100.0), Vec3::new(0.0, 0.0, 100.0), BLUE);
-        }
-}
-
-// WASD + QE movement and arrow keys for camera rotation
-fn fly_camera(
-    mut query: Query<(&mut Transform, &mut FlyCamera)>,
-    keys: Res<ButtonInput<KeyCode>>,
-    time: Res<Time>,
-) {
-    let speed = 5.0;
-    let rot_speed = 1.5; // radians/sec
-
-    for (mut transform, mut camera) in &mut query {
-        // spin on Y axis
-        if keys.pressed(KeyCode::ArrowLeft) {
-            camera.yaw += rot_speed * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::ArrowRight) {
-            camera.yaw -= rot_speed * time.delta_secs();
-        }
-        // pitch up/down
-        if keys.pressed(KeyCode::ArrowUp) {
-            camera.pitch += rot_speed * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::ArrowDown) {
-            camera.pitch -= rot_speed * time.delta_secs();
-        }
-        camera.pitch = camera.pitch.clamp(-1.54, 1.54); // clamp pitch to avoid flipping
-
-        // apply yaw and pitch rotation to the camera
-        transform.rotation =
-            Quat::from_axis_angle(Vec3::Y, camera.yaw) *
-            Quat::from_axis_angle(Vec3::X, camera.pitch);
-
-        // movement (WASD for horizontal, QE for vertical)
-        let mut direction = Vec3::ZERO;
-        if keys.pressed(KeyCode::KeyW) {
-            direction += *transform.forward() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyS) {
-            direction -= *transform.forward() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyA) {
-            direction -= *transform.right() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyD) {
-            direction += *transform.right() * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyQ) {
-            direction += Vec3::Y * time.delta_secs();
-        }
-        if keys.pressed(KeyCode::KeyE) {
-            direction -= Vec3::Y * time.delta_secs();
-        }
-        if direction.length_squared() > 0.0 {
-            transform.translation += direction.normalize() * speed * time.delta_secs();
-        }
-        println!("Camera Position: {:?}", transform.translation);
-        println!("Camera Rotation: {:?}", transform.rotation);
-    }
-}
-
-// Toggle full screen for the game view camera with F11
+// Toggle full screen for the game view camera with the toggle_fullscreen action (F11)
 fn full_screen_toggle(
     mut full_screen: Single<&mut FullScreen>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::F11) {
+    if actions.just_pressed(action::TOGGLE_FULLSCREEN) {
         full_screen.enabled = !full_screen.enabled;
         println!("Full Screen Mode: {}", full_screen.enabled);
     }
@@ -312,7 +223,7 @@ fn setup_viewpoints(
     mut ui_node: Single<&mut Node>,
     windows: Query<&Window, With<PrimaryWindow>>,
     full_screen: Single<&mut FullScreen>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
 ) {
     let window = windows.single();
     let width = window.resolution.physical_width();
@@ -356,5 +267,5 @@ fn setup_viewpoints(
             _ => {}
         }
     }
-    full_screen_toggle(full_screen, keyboard_input);
+    full_screen_toggle(full_screen, actions);
 }
@@ -5,6 +5,7 @@ use bevy::color::Color;
 use bevy::math::Vec2;
 use bevy::prelude::{Commands, Component, Gizmos, Mut, Query, Res, Time};
 use bevy::window::{WindowPlugin, Window};
+use blog_common::{PointerPlugin, Pointers};
 
 
 pub fn main() {
@@ -17,12 +18,13 @@ pub fn main() {
         ..default()
     }))
         .add_systems(Startup, setup)
+        .add_plugins(PointerPlugin)
         .add_plugins(SpritePlugin)
         .run();
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 }
 
 pub struct SpritePlugin;
@@ -30,13 +32,16 @@ pub struct SpritePlugin;
 impl Plugin for SpritePlugin {
     fn build (&self, app: &mut App) {
         app.add_systems(Startup, sprite_start);
-        app.add_systems(Update, sprite_movement);
+        app.add_systems(Update, (sprite_steer, sprite_movement).chain());
     }
 }
 
 #[derive(Component)]
 pub struct Sprite {
     position: Vec2,
+    center: Vec2,        // the middle of the circle the worm swims around
+    target_center: Vec2, // where the circle is heading, the last place that was clicked or touched
+    circle_radius: f32,  // pinch on a touch screen to change it
     // direction: Direction, // Remove if unused
     circle_angle: f32,
 }
@@ -45,6 +50,9 @@ fn sprite_start(mut commands: Commands) {
     for _ in 0..1 {
         commands.spawn(Sprite {
             position: Vec2::new(0.0, 0.0),
+            center: Vec2::ZERO,
+            target_center: Vec2::ZERO,
+            circle_radius: 150.0,
             // direction: Direction::Up, // Remove if unused
             circle_angle: 0.0,
         });
@@ -56,7 +64,7 @@ fn sprite_update(
     time: &Res<Time>
 ){
     // Parameters for the circular path
-    let circle_radius = 150.0;
+    let circle_radius = sprite.circle_radius;
     let circle_speed = 0.5; // radians per second
 
     // Update the angle
@@ -67,8 +75,12 @@ fn sprite_update(
         sprite.circle_angle -= std::f32::consts::TAU;
     }
 
+    // Glide the circle towards the target instead of jumping there
+    let glide = 1.0 - f32::exp(-3.0 * time.delta_secs());
+    sprite.center = sprite.center.lerp(sprite.target_center, glide);
+
     // Calculate new base position on the circle
-    sprite.position = Vec2::new(
+    sprite.position = sprite.center + Vec2::new(
         circle_radius * sprite.circle_angle.cos(),
         circle_radius * sprite.circle_angle.sin(),
     );
@@ -93,9 +105,9 @@ fn sprite_animate(
 
         // The worm's body follows the circle, but each segment is offset along the tangent
         let angle = sprite.circle_angle + (i as f32 / steps as f32) * 0.5; // spread segments along the circle
-        let base = Vec2::new(
-            150.0 * angle.cos(),
-            150.0 * angle.sin(),
+        let base = sprite.center + Vec2::new(
+            sprite.circle_radius * angle.cos(),
+            sprite.circle_radius * angle.sin(),
         );
         // Tangent vector (perpendicular to radius)
         let tangent = Vec2::new(-angle.sin(), angle.cos());
@@ -110,6 +122,37 @@ fn sprite_animate(
     }
 }
 
+// Click, tap or drag a finger to call the worm over, pinch with two fingers to make its circle bigger or smaller.
+// Mouse and touch come through the same pointers, so this works in the browser on a phone too.
+fn sprite_steer(
+    pointers: Res<Pointers>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    let (camera, camera_transform) = *camera;
+    let pressed: Vec<Vec2> = pointers
+        .iter()
+        .filter(|(_, pointer)| pointer.pressed)
+        .map(|(_, pointer)| pointer.position)
+        .collect();
+    // between the fingers when there are two of them
+    let target = if pressed.is_empty() {
+        None
+    } else {
+        let middle = pressed.iter().sum::<Vec2>() / pressed.len() as f32;
+        camera.viewport_to_world_2d(camera_transform, middle).ok()
+    };
+
+    for mut sprite in &mut sprite_query {
+        if let Some(target) = target {
+            sprite.target_center = target;
+        }
+        if let Some(gesture) = pointers.gesture() {
+            sprite.circle_radius = (sprite.circle_radius * gesture.pinch).clamp(40.0, 400.0);
+        }
+    }
+}
+
 // The sprite is animated by changing its translation depending on the time that has passed since the last frame.
 pub fn sprite_movement(
     time: Res<Time>,